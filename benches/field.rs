@@ -0,0 +1,72 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rescue_prime::{
+    inner_product, ntt, pow7_state, standard_mds, FieldElement, ALPHA, ALPHA_INV, WIDTH,
+};
+
+fn bench_field(c: &mut Criterion) {
+    let x = FieldElement::new(0x0123456789ABCDEF);
+    let y = FieldElement::new(0xFEDCBA9876543210);
+
+    c.bench_function("field/mul", |b| b.iter(|| black_box(x) * black_box(y)));
+    c.bench_function("field/inv", |b| b.iter(|| black_box(x).inv()));
+    c.bench_function("field/exp_u64/alpha", |b| b.iter(|| black_box(x).exp_u64(ALPHA)));
+    c.bench_function("field/mul_small/3", |b| b.iter(|| black_box(x).mul_small(black_box(3))));
+    c.bench_function("field/mul/3", |b| b.iter(|| black_box(x) * FieldElement::new(black_box(3))));
+    c.bench_function("field/pow7", |b| b.iter(|| black_box(x).pow7()));
+    c.bench_function("field/pow7_state/12", |b| {
+        b.iter(|| {
+            let mut state = black_box([x; WIDTH]);
+            pow7_state(&mut state);
+            state
+        })
+    });
+    c.bench_function("field/exp_u64/alpha_inv", |b| b.iter(|| black_box(x).exp_u64(ALPHA_INV)));
+}
+
+fn bench_mds(c: &mut Criterion) {
+    let mds = standard_mds();
+    let mut state = [FieldElement::ZERO; WIDTH];
+    for (i, element) in state.iter_mut().enumerate() {
+        *element = FieldElement::new((i as u64).wrapping_mul(0x9E3779B97F4A7C15));
+    }
+
+    // one reduction per product and one modular addition per term
+    c.bench_function("mds/eager", |b| {
+        b.iter(|| {
+            let state = black_box(&state);
+            mds.map(|row| {
+                row.iter()
+                    .zip(state.iter())
+                    .fold(FieldElement::ZERO, |acc, (&x, &y)| acc + x * y)
+            })
+        })
+    });
+
+    // a single reduction per row, as done by the permutation
+    c.bench_function("mds/lazy", |b| {
+        b.iter(|| {
+            let state = black_box(&state);
+            let mut result = [FieldElement::ZERO; WIDTH];
+            for (res, row) in result.iter_mut().zip(mds.iter()) {
+                *res = inner_product(row, state);
+            }
+            result
+        })
+    });
+}
+
+fn bench_ntt(c: &mut Criterion) {
+    // run with and without the rayon feature to compare the parallel transform
+    for log_n in [10, 14, 16, 18] {
+        let values: Vec<_> = (0..1u64 << log_n)
+            .map(|i| FieldElement::new(i.wrapping_mul(0x9E3779B97F4A7C15)))
+            .collect();
+
+        c.bench_function(&format!("ntt/2^{log_n}"), |b| {
+            b.iter_batched(|| values.clone(), |mut values| ntt(&mut values), BatchSize::LargeInput)
+        });
+    }
+}
+
+criterion_group!(benches, bench_field, bench_mds, bench_ntt);
+criterion_main!(benches);
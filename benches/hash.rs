@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rescue_prime::{
+    hash, hash_bytes, hash_many, merkle_root, rescue_xlix, standard_mds, standard_round_constants,
+    FieldElement, WIDTH,
+};
+
+fn bench_permutation(c: &mut Criterion) {
+    let round_constants = standard_round_constants();
+    let mds = standard_mds();
+    let mut state = [FieldElement::ZERO; WIDTH];
+    for (i, element) in state.iter_mut().enumerate() {
+        *element = FieldElement::new(i as u64);
+    }
+
+    c.bench_function("rescue_xlix", |b| {
+        b.iter(|| rescue_xlix(black_box(&mut state), round_constants, mds))
+    });
+}
+
+fn bench_hash_bytes(c: &mut Criterion) {
+    let bytes: Vec<u8> = (0..1024u32).map(|i| i as u8).collect();
+
+    c.bench_function("hash_bytes/1KB", |b| b.iter(|| hash_bytes(black_box(&bytes))));
+}
+
+fn bench_hash_many(c: &mut Criterion) {
+    // a batch of Merkle-tree leaves, each made of 16 elements
+    let inputs: Vec<Vec<FieldElement>> = (0..256u64)
+        .map(|i| (0..16u64).map(|j| FieldElement::new(i * 16 + j)).collect())
+        .collect();
+
+    c.bench_function("hash_many/256x16", |b| {
+        b.iter_batched(
+            || inputs.iter().map(|input| input.as_slice()).collect::<Vec<_>>(),
+            |inputs| hash_many(black_box(&inputs)),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_merkle_root(c: &mut Criterion) {
+    // run with and without the rayon feature to compare the parallel levels
+    for log_n in [8, 10, 12] {
+        let leaves: Vec<_> = (0..1u64 << log_n).map(|i| hash(&[FieldElement::new(i)])).collect();
+
+        c.bench_function(&format!("merkle_root/2^{log_n}"), |b| {
+            b.iter(|| merkle_root(black_box(&leaves)))
+        });
+    }
+}
+
+criterion_group!(benches, bench_permutation, bench_hash_bytes, bench_hash_many, bench_merkle_root);
+criterion_main!(benches);
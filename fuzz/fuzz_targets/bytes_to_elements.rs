@@ -0,0 +1,24 @@
+#![no_main]
+
+// Deserializing a byte string of any length must either round trip through
+// `elements_to_bytes` with canonical elements, or be rejected because of its
+// length or of a non-canonical chunk, and never panic.
+
+use libfuzzer_sys::fuzz_target;
+use rescue_prime::{bytes_to_elements, elements_to_bytes, PRIME};
+
+fuzz_target!(|data: &[u8]| {
+    match bytes_to_elements(data) {
+        Ok(elements) => {
+            assert_eq!(elements.len() * 8, data.len());
+            assert!(elements.iter().all(|element| element.value() < PRIME));
+            assert_eq!(elements_to_bytes(&elements), data);
+        }
+        Err(_) => {
+            let non_canonical = data
+                .chunks_exact(8)
+                .any(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()) >= PRIME);
+            assert!(data.len() % 8 != 0 || non_canonical);
+        }
+    }
+});
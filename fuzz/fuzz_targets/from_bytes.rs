@@ -0,0 +1,33 @@
+#![no_main]
+
+// Deserializing 8 bytes must accept exactly the canonical values, in both byte
+// orders, and never panic.
+
+use libfuzzer_sys::fuzz_target;
+use rescue_prime::{FieldElement, PRIME};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(bytes) = <[u8; 8]>::try_from(data) else {
+        return;
+    };
+
+    let value = u64::from_le_bytes(bytes);
+    match FieldElement::from_bytes(&bytes) {
+        Ok(element) => {
+            assert!(element.value() < PRIME);
+            assert_eq!(element.value(), value);
+            assert_eq!(element.to_bytes(), bytes);
+        }
+        Err(_) => assert!(value >= PRIME),
+    }
+
+    let value = u64::from_be_bytes(bytes);
+    match FieldElement::from_bytes_be(&bytes) {
+        Ok(element) => {
+            assert!(element.value() < PRIME);
+            assert_eq!(element.value(), value);
+            assert_eq!(element.to_bytes_be(), bytes);
+        }
+        Err(_) => assert!(value >= PRIME),
+    }
+});
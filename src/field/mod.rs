@@ -0,0 +1,92 @@
+use core::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+// FIELD TRAIT
+// =============================================================================
+
+/// A prime field over which the Rescue-XLIX permutation and the sponge can be
+/// instantiated.
+///
+/// The permutation only needs the field operations, the exponents of the S-box
+/// and a way to raise elements to these exponents, so that it can be reused over
+/// other STARK-friendly primes. `FieldElement` implements this trait for the
+/// 64-bit field used by the rest of the crate.
+pub trait Field:
+    Copy
+    + Debug
+    + Eq
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + SubAssign
+    + Mul<Output = Self>
+    + MulAssign
+    + Neg<Output = Self>
+    + 'static
+{
+    /// The additive identity of the field.
+    const ZERO: Self;
+
+    /// The multiplicative identity of the field.
+    const ONE: Self;
+
+    /// The exponent of the S-box, which must be coprime to the order of the
+    /// multiplicative group, so that x -> x^ALPHA is a permutation of the field.
+    const ALPHA: u64;
+
+    /// The inverse of ALPHA modulo the order of the multiplicative group, so
+    /// that x -> x^ALPHA_INV undoes the S-box.
+    const ALPHA_INV: u64;
+
+    /// Return the element congruent to the value modulo the characteristic.
+    fn from_u64(value: u64) -> Self;
+
+    /// Return the multiplicative inverse of this element, which must not be ZERO.
+    fn inv(self) -> Self;
+
+    /// Return this element raised to the given power.
+    fn exp_u64(self, power: u64) -> Self;
+
+    /// Return the square of this element.
+    #[inline(always)]
+    fn square(self) -> Self {
+        self * self
+    }
+
+    /// Return the sum of a[i] * b[i] over all i.
+    ///
+    /// Fields can override this to reduce the sum of products only once.
+    ///
+    /// # Panics
+    /// Panics if a and b do not have the same length.
+    #[inline(always)]
+    fn inner_product(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len(), "The inner product needs slices of the same length.");
+        a.iter().zip(b.iter()).fold(Self::ZERO, |acc, (&x, &y)| acc + x * y)
+    }
+
+    /// Raise every element of the state to the power ALPHA.
+    ///
+    /// Fields can override this with an addition chain for their exponent.
+    #[inline(always)]
+    fn sbox<const WIDTH: usize>(state: &mut [Self; WIDTH]) {
+        for element in state.iter_mut() {
+            *element = element.exp_u64(Self::ALPHA);
+        }
+    }
+
+    /// Raise every element of the state to the power ALPHA_INV.
+    ///
+    /// Fields can override this with an addition chain for their exponent, which
+    /// is usually much cheaper than square-and-multiply.
+    #[inline(always)]
+    fn inv_sbox<const WIDTH: usize>(state: &mut [Self; WIDTH]) {
+        for element in state.iter_mut() {
+            *element = element.exp_u64(Self::ALPHA_INV);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// Batch arithmetic on 4 field elements at a time with AVX2.
+//
+// AVX2 has no unsigned 64-bit comparison, so comparisons flip the sign bit of
+// both operands and use the signed one instead. Every step mirrors the scalar
+// arithmetic, and the results are canonical, so they are identical to the ones
+// of the scalar path.
+
+use core::arch::x86_64::{
+    __m256i, _mm256_add_epi64, _mm256_and_si256, _mm256_andnot_si256, _mm256_cmpgt_epi64,
+    _mm256_loadu_si256, _mm256_mul_epu32, _mm256_or_si256, _mm256_set1_epi64x, _mm256_slli_epi64,
+    _mm256_srli_epi64, _mm256_storeu_si256, _mm256_sub_epi64, _mm256_xor_si256,
+};
+
+use super::{FieldElement, PRIME};
+
+/// The number of field elements in a vector.
+pub(super) const LANES: usize = 4;
+
+/// 2^64 - PRIME = 2^32 - 1, which is also 2^64 modulo PRIME.
+const EPSILON: u64 = 0xFFFFFFFF;
+
+// BATCH OPERATIONS
+// =============================================================================
+
+/// Write a[i] + b[i] into out[i] for all the full vectors of the inputs, and
+/// return the number of elements written.
+pub(super) fn add_batch(a: &[FieldElement], b: &[FieldElement], out: &mut [FieldElement]) -> usize {
+    // SAFETY: this module is only compiled for targets with AVX2 support.
+    apply(a, b, out, |x, y| unsafe { add(x, y) })
+}
+
+/// Write a[i] * b[i] into out[i] for all the full vectors of the inputs, and
+/// return the number of elements written.
+pub(super) fn mul_batch(a: &[FieldElement], b: &[FieldElement], out: &mut [FieldElement]) -> usize {
+    // SAFETY: this module is only compiled for targets with AVX2 support.
+    apply(a, b, out, |x, y| unsafe { mul(x, y) })
+}
+
+// HELPER FUNCTIONS
+// =============================================================================
+
+/// Apply the operation to every full vector of the inputs.
+#[inline(always)]
+fn apply(
+    a: &[FieldElement],
+    b: &[FieldElement],
+    out: &mut [FieldElement],
+    op: impl Fn(__m256i, __m256i) -> __m256i,
+) -> usize {
+    let chunks = a.chunks_exact(LANES).zip(b.chunks_exact(LANES));
+    for ((a, b), out) in chunks.zip(out.chunks_exact_mut(LANES)) {
+        // SAFETY: FieldElement is a transparent wrapper around u64, and every
+        // chunk holds exactly LANES = 4 of them, i.e. 256 bits. The loads and
+        // the store are unaligned.
+        unsafe {
+            let x = _mm256_loadu_si256(a.as_ptr() as *const __m256i);
+            let y = _mm256_loadu_si256(b.as_ptr() as *const __m256i);
+            _mm256_storeu_si256(out.as_mut_ptr() as *mut __m256i, op(x, y));
+        }
+    }
+    a.len() / LANES * LANES
+}
+
+/// Return x + y for canonical x and y.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn add(x: __m256i, y: __m256i) -> __m256i {
+    // If the sum overflows, or is at least PRIME, then adding EPSILON wrapping
+    // around 2^64 gives the canonical sum in both cases.
+    let sum = _mm256_add_epi64(x, y);
+    let overflow = gt(x, sum);
+    let canonical = gt(splat(PRIME), sum);
+    let mask = _mm256_or_si256(overflow, _mm256_xor_si256(canonical, splat(u64::MAX)));
+    _mm256_add_epi64(sum, _mm256_and_si256(mask, splat(EPSILON)))
+}
+
+/// Return x * y for canonical x and y.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mul(x: __m256i, y: __m256i) -> __m256i {
+    let (high, low) = mul_wide(x, y);
+    canonicalize(reduce(high, low))
+}
+
+/// Return the high and low 64 bits of the 128-bit products x * y, computed from
+/// the four products of their 32-bit halves.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mul_wide(x: __m256i, y: __m256i) -> (__m256i, __m256i) {
+    let x_high = _mm256_srli_epi64::<32>(x);
+    let y_high = _mm256_srli_epi64::<32>(y);
+
+    // _mm256_mul_epu32 multiplies the low 32 bits of every lane.
+    let ll = _mm256_mul_epu32(x, y);
+    let lh = _mm256_mul_epu32(x, y_high);
+    let hl = _mm256_mul_epu32(x_high, y);
+    let hh = _mm256_mul_epu32(x_high, y_high);
+
+    // None of the sums below can overflow, since (2^32 - 1)^2 + 2 * (2^32 - 1) < 2^64.
+    let mask = splat(0xFFFFFFFF);
+    let t1 = _mm256_add_epi64(lh, _mm256_srli_epi64::<32>(ll));
+    let t2 = _mm256_add_epi64(hl, _mm256_and_si256(t1, mask));
+    let low = _mm256_or_si256(_mm256_slli_epi64::<32>(t2), _mm256_and_si256(ll, mask));
+    let high = _mm256_add_epi64(
+        hh,
+        _mm256_add_epi64(_mm256_srli_epi64::<32>(t1), _mm256_srli_epi64::<32>(t2)),
+    );
+    (high, low)
+}
+
+/// Reduce 2^64 * high + low modulo PRIME, with the same steps as the scalar
+/// `reduce`, to a value that is congruent but not necessarily canonical.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn reduce(high: __m256i, low: __m256i) -> __m256i {
+    // 2^96 = -1 (mod p)
+    let high_high = _mm256_srli_epi64::<32>(high);
+    let diff = _mm256_sub_epi64(low, high_high);
+    let under = gt(high_high, low);
+    // Adding PRIME wrapping around 2^64 is the same as subtracting EPSILON.
+    let diff = _mm256_sub_epi64(diff, _mm256_and_si256(under, splat(EPSILON)));
+
+    // 2^64 = 2^32 - 1 (mod p)
+    let high_low = _mm256_and_si256(high, splat(0xFFFFFFFF));
+    let product = _mm256_sub_epi64(_mm256_slli_epi64::<32>(high_low), high_low);
+
+    // Subtracting PRIME wrapping around 2^64 is the same as adding EPSILON.
+    let result = _mm256_add_epi64(diff, product);
+    let over = gt(diff, result);
+    _mm256_add_epi64(result, _mm256_and_si256(over, splat(EPSILON)))
+}
+
+/// Subtract PRIME from the values which are at least PRIME.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn canonicalize(x: __m256i) -> __m256i {
+    let canonical = gt(splat(PRIME), x);
+    _mm256_add_epi64(x, _mm256_andnot_si256(canonical, splat(EPSILON)))
+}
+
+/// Return all ones in the lanes where x > y as unsigned integers, and zeros
+/// elsewhere.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn gt(x: __m256i, y: __m256i) -> __m256i {
+    let sign = splat(1 << 63);
+    _mm256_cmpgt_epi64(_mm256_xor_si256(x, sign), _mm256_xor_si256(y, sign))
+}
+
+/// Return a vector with the value in every lane.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn splat(value: u64) -> __m256i {
+    _mm256_set1_epi64x(value as i64)
+}
@@ -1,22 +1,37 @@
-use core::convert::TryFrom;
-use std::{
-    fmt::{Display, Formatter, Result as FmtResult},
+use alloc::vec::Vec;
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Display, Formatter, LowerHex, Result as FmtResult, UpperHex},
+    hash::{Hash, Hasher},
+    iter::{Product, Sum},
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
 };
 
+#[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "avx2"))]
+mod avx2;
+
 #[cfg(test)]
 mod tests;
 
-use crate::utils::errors::FieldError;
+use crate::{field::Field, utils::errors::FieldError};
 
 // CONSTANTS
 // =============================================================================
 
 /// Prime number that defines the field the FieldElement is in. It is 2^64 - 2^32 + 1.
-const PRIME: u64 = 0xFFFFFFFF00000001;
+pub const PRIME: u64 = 0xFFFFFFFF00000001;
+
+/// The exponent of the S-box, x -> x^ALPHA. It is the smallest integer greater
+/// than 1 that is coprime to PRIME - 1, so that the S-box is a permutation, as
+/// found by `smallest_alpha`.
+pub const ALPHA: u64 = 7;
 
-const ZERO: FieldElement = FieldElement { value: 0 };
-const ONE: FieldElement = FieldElement { value: 1 };
+/// The exponent of the inverse S-box, x -> x^ALPHA_INV. It is the inverse of
+/// ALPHA modulo PRIME - 1, i.e. ALPHA * ALPHA_INV = 1 (mod PRIME - 1), as
+/// computed by `alpha_inverse`.
+pub const ALPHA_INV: u64 = 10540996611094048183;
 
 // STRUCTS
 // =============================================================================
@@ -25,15 +40,37 @@ const ONE: FieldElement = FieldElement { value: 1 };
 /// as a u64, but it is not valid to create a FieldElement with a value >=
 /// PRIME.
 #[derive(Clone, Copy, Debug)]
-struct FieldElement {
+#[repr(transparent)]
+pub struct FieldElement {
     value: u64,
 }
 
-/// IMPLEMENTATIONS
-/// =============================================================================
+// IMPLEMENTATIONS
+// =============================================================================
 
-#[allow(dead_code)]
 impl FieldElement {
+    /// The additive identity of the field.
+    pub const ZERO: FieldElement = FieldElement { value: 0 };
+
+    /// The multiplicative identity of the field.
+    pub const ONE: FieldElement = FieldElement { value: 1 };
+
+    /// A generator of the multiplicative group of the field, i.e. an element of
+    /// order PRIME - 1.
+    pub const GENERATOR: FieldElement = FieldElement { value: 7 };
+
+    /// The largest power of two dividing PRIME - 1, i.e. PRIME - 1 = 2^32 * (2^32 - 1).
+    pub const TWO_ADICITY: u32 = 32;
+
+    /// A quadratic non-residue of the field, used by `sqrt`. Since it is the
+    /// GENERATOR, which generates the whole multiplicative group, it cannot be a square.
+    pub const QUADRATIC_NON_RESIDUE: FieldElement = Self::GENERATOR;
+
+    /// A primitive 2^32-th root of unity, computed as GENERATOR^((PRIME - 1) / 2^32).
+    pub const TWO_ADIC_ROOT_OF_UNITY: FieldElement = FieldElement {
+        value: 1753635133440165772,
+    };
+
     /// Create a new FieldElement. If the value is >= PRIME, then the value is
     /// reduced modulo PRIME.
     pub const fn new(value: u64) -> FieldElement {
@@ -42,38 +79,186 @@ impl FieldElement {
         }
     }
 
+    /// Return the sum of two FieldElements in a const context, e.g. to derive
+    /// parameters as constants. This is the same as the Add impl, which cannot
+    /// be called at compile time.
+    pub const fn const_add(self, other: FieldElement) -> FieldElement {
+        let (result, carry) = self.value.overflowing_add(other.value);
+        Self::new(result.wrapping_sub(PRIME * (carry as u64)))
+    }
+
+    /// Return the product of two FieldElements in a const context, e.g. to
+    /// derive parameters as constants. This is the same as the Mul impl, which
+    /// cannot be called at compile time.
+    pub const fn const_mul(self, other: FieldElement) -> FieldElement {
+        Self::new(reduce((self.value as u128) * (other.value as u128)))
+    }
+
+    /// Create a new FieldElement from any 128-bit value, reduced modulo PRIME to
+    /// its canonical representative. This is meant for wide accumulations, e.g.
+    /// of products of field elements, which need a single reduction at the end.
+    #[inline]
+    pub fn from_wide(value: u128) -> FieldElement {
+        Self::new(reduce(value))
+    }
+
+    /// Create a new FieldElement from a 128-bit value, e.g. the result of an
+    /// external computation, reduced modulo PRIME. This is the same as
+    /// `from_wide` and the `From<u128>` impl.
+    #[inline]
+    pub fn from_u128(x: u128) -> FieldElement {
+        Self::from_wide(x)
+    }
+
+    /// Create a new FieldElement from a value that is already reduced, without
+    /// performing the modulo. Return an error if the value is >= PRIME.
+    pub fn from_canonical(value: u64) -> Result<FieldElement, FieldError> {
+        if value >= PRIME {
+            Err(FieldError::NonCanonical)
+        } else {
+            Ok(FieldElement { value })
+        }
+    }
+
+    /// Create a new FieldElement from a value that is already reduced, without
+    /// performing the modulo nor checking the value, e.g. for the output of a
+    /// reduction that is known to be canonical. The check is only done in debug
+    /// builds. Use `new` or `from_canonical` when the value may be >= PRIME.
+    ///
+    /// # Safety
+    /// The value must be less than PRIME. Every FieldElement is assumed to hold
+    /// its canonical value, so a larger value breaks the arithmetic and the
+    /// comparisons of the field.
+    #[inline(always)]
+    pub const unsafe fn from_raw(value: u64) -> FieldElement {
+        debug_assert!(value < PRIME, "the value must be less than PRIME");
+        FieldElement { value }
+    }
+
+    /// Sample a uniformly random FieldElement. Values in [PRIME, 2^64) are
+    /// rejected and resampled rather than reduced, so that there is no modulo
+    /// bias. This happens with probability less than 2^-32 per sample.
+    #[cfg(feature = "rand")]
+    pub fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> FieldElement {
+        loop {
+            let value = rng.next_u64();
+            if value < PRIME {
+                return FieldElement { value };
+            }
+        }
+    }
+
     /// Return the value of the FieldElement.
     #[inline]
     pub fn value(&self) -> u64 {
         self.value
     }
 
+    /// Return the canonical value of the FieldElement, i.e. its representative
+    /// in 0..PRIME. This is the same as `value`, and spells out the guarantee.
+    #[inline]
+    pub fn as_canonical_u64(self) -> u64 {
+        self.value
+    }
+
+    /// Return the Montgomery form of the FieldElement with R = 2^64, i.e. the
+    /// canonical representative of value * 2^64 mod PRIME.
+    ///
+    /// This is for interoperability with code using Montgomery arithmetic, e.g.
+    /// `ark-ff`. Values in Montgomery form are multiplied with `mul_montgomery`
+    /// and converted back with `from_montgomery`.
+    #[inline]
+    pub fn to_montgomery(self) -> u64 {
+        reduce((self.value as u128) << 64) % PRIME
+    }
+
+    /// Return the FieldElement of the given Montgomery form with R = 2^64, i.e.
+    /// value * 2^-64 mod PRIME, undoing `to_montgomery`. Values >= PRIME are
+    /// accepted and reduced modulo PRIME.
+    #[inline]
+    pub fn from_montgomery(value: u64) -> FieldElement {
+        FieldElement {
+            value: montgomery_reduce(value as u128),
+        }
+    }
+
+    /// Return the Montgomery form of the product of the elements with the given
+    /// Montgomery forms, i.e. a * b * 2^-64 mod PRIME, with the Montgomery
+    /// reduction instead of `reduce`. For all x and y,
+    /// `from_montgomery(mul_montgomery(x.to_montgomery(), y.to_montgomery()))`
+    /// is x * y.
+    ///
+    /// The inputs must be less than PRIME, as returned by `to_montgomery`; this
+    /// is only checked in debug builds.
+    #[inline]
+    pub fn mul_montgomery(a: u64, b: u64) -> u64 {
+        debug_assert!(a < PRIME && b < PRIME, "Montgomery forms must be less than PRIME");
+        montgomery_reduce((a as u128) * (b as u128))
+    }
+
     /// Return the summation of the field element with itself.
     #[inline]
     pub fn double(&self) -> Self {
-        let (res, carry) = self.value.overflowing_add(self.value);
-        Self::new(res.wrapping_sub(PRIME * (carry as u64)))
+        *self + *self
+    }
+
+    /// Return the product of the field element with the integer n.
+    ///
+    /// For n < 2^32, the product of the value with n is less than 2^96, i.e.
+    /// low + 2^64 * high with high < 2^32. Since 2^64 = 2^32 - 1 (mod PRIME),
+    /// it is folded into low + high * (2^32 - 1), which fits in 64 bits up to a
+    /// single carry, without the 128-bit `reduce` of a general multiplication.
+    /// Larger n fall back to `from_wide`.
+    #[inline]
+    pub fn mul_small(self, n: u64) -> Self {
+        if n > u32::MAX as u64 {
+            return Self::from_wide(self.value as u128 * n as u128);
+        }
+
+        let product = self.value as u128 * n as u128;
+        let low = product as u64;
+        let high = (product >> 64) as u64;
+
+        // high * (2^32 - 1) <= (2^32 - 1)^2, so after a carry the sum is small
+        // enough for the 2^64 = 2^32 - 1 correction not to overflow again.
+        let (sum, over) = low.overflowing_add((high << 32) - high);
+        let sum = sum.wrapping_add((over as u64) * 0xFFFFFFFF);
+
+        let value = if sum >= PRIME { sum - PRIME } else { sum };
+        FieldElement { value }
     }
 
     /// Return the exponentiation of the field element with `pow` field element.
+    ///
+    /// NOTE: Since `pow` is a field element, the exponent is always reduced
+    ///       modulo PRIME before it is used, which is not the same as reducing
+    ///       it modulo PRIME - 1. For example, `a.exp(FieldElement::new(PRIME))`
+    ///       returns ONE instead of `a`. Use `exp_u64` when the exponent can be
+    ///       greater than or equal to PRIME.
     #[inline]
     pub fn exp(self, pow: Self) -> Self {
+        self.exp_u64(pow.value())
+    }
+
+    /// Return the exponentiation of the field element with the `pow` integer.
+    /// The exponent is used as is, without any modular reduction.
+    #[inline]
+    pub fn exp_u64(self, pow: u64) -> Self {
         let mut base = self;
 
-        if pow == ZERO {
-            return ONE;
-        } else if base == ZERO {
-            return ZERO;
+        if pow == 0 {
+            return Self::ONE;
+        } else if base == Self::ZERO {
+            return Self::ZERO;
         }
 
-        // TODO: come up with an implementation that takes constant time to execute.
-        // This implementation is not constant time.
+        // This implementation is not constant time, see `exp_ct` for one that is.
         // Checks if the least significant bit is 1. If it is, then the result is
         // the base. Otherwise, the result is 1.
-        let mut res = if (pow.value & 1) == 1 { base } else { ONE };
+        let mut res = if (pow & 1) == 1 { base } else { Self::ONE };
 
         // Shift the bits of the exponent to the right by 1.
-        let mut pow_val = pow.value >> 1;
+        let mut pow_val = pow >> 1;
 
         // While the exponent is greater than 0, square the base and multiply the
         // result by the base if the least significant bit of the exponent is 1.
@@ -95,12 +280,78 @@ impl FieldElement {
         res
     }
 
+    /// Return the exponentiation of the field element with an exponent of any
+    /// size, given as little-endian u64 limbs, i.e. the exponent is the sum of
+    /// `exp[i] * 2^(64 * i)`.
+    ///
+    /// This is left-to-right square-and-multiply over all the bits of the limbs,
+    /// starting from the most significant one, and like `exp_u64` it is not
+    /// constant time.
+    pub fn pow_limbs(self, exp: &[u64]) -> Self {
+        let mut res = Self::ONE;
+        for &limb in exp.iter().rev() {
+            for i in (0..u64::BITS).rev() {
+                res = res.square();
+                if (limb >> i) & 1 == 1 {
+                    res *= self;
+                }
+            }
+        }
+        res
+    }
+
+    /// Return the exponentiation of the field element with the `pow` integer,
+    /// performing the same sequence of operations whatever the bits of `pow`.
+    ///
+    /// This uses the Montgomery ladder over all 64 bits of the exponent, with
+    /// branch-free conditional swaps, so that its running time does not leak the
+    /// exponent. It is roughly twice as slow as `exp_u64`, which should be
+    /// preferred whenever the exponent is public.
+    pub fn exp_ct(self, pow: u64) -> Self {
+        // Invariant: r1 = r0 * self, with r0 = self^k for the bits k of pow
+        // processed so far.
+        let mut r0 = Self::ONE;
+        let mut r1 = self;
+
+        for i in (0..u64::BITS).rev() {
+            let bit = (pow >> i) & 1;
+
+            // If the bit is 0, then r0, r1 = r0^2, r0 * r1. Otherwise, then
+            // r0, r1 = r0 * r1, r1^2, which is the same with r0 and r1 swapped.
+            conditional_swap(&mut r0, &mut r1, bit);
+            r1 *= r0;
+            r0 = r0.square();
+            conditional_swap(&mut r0, &mut r1, bit);
+        }
+
+        r0
+    }
+
+    /// Return whether the field element is equal to the other one, comparing
+    /// their canonical values without branching.
+    #[cfg(feature = "subtle")]
+    pub fn ct_eq(self, other: FieldElement) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self, &other)
+    }
+
+    /// Return a if choice is 0 and b if choice is 1, without branching.
+    #[cfg(feature = "subtle")]
+    pub fn conditional_select(
+        a: &FieldElement,
+        b: &FieldElement,
+        choice: subtle::Choice,
+    ) -> FieldElement {
+        subtle::ConditionallySelectable::conditional_select(a, b, choice)
+    }
+
     /// Return the inverse of the FieldElement. According to the Fermat Little
     /// Theorem, the inverse of a number is the number raised to the power of
     /// PRIME - 2.
     ///
     /// NOTE: The inverse of zero is undefined. The caller must ensure that
-    ///       this function is never called with the zero element.
+    ///       this function is never called with the zero element. This is only
+    ///       checked in debug builds; in release builds the inverse of zero
+    ///       silently comes out as zero. Use `try_inv` when the element may be zero.
     ///
     /// Mathematically, this is equivalent to:
     ///             $a^(p-1)     = 1 (mod p)$
@@ -112,7 +363,7 @@ impl FieldElement {
     /// Adapted from: https://github.com/facebook/winterfell/blob/d238a1ecc8da42179d0b8a06c0d4a510256aa0a6/math/src/field/f64/mod.rs#L136-L164
     #[inline]
     pub fn inv(self) -> Self {
-        debug_assert!(self != ZERO, "The inverse of zero is undefined.");
+        debug_assert!(self != Self::ZERO, "The inverse of zero is undefined.");
 
         // compute base^(M - 2) using 72 multiplications
         // The exponent M - 2 is represented in binary as:
@@ -149,9 +400,26 @@ impl FieldElement {
         t63.square() * self
     }
 
+    /// Return the inverse of the FieldElement, or None if it is zero.
+    #[inline]
+    pub fn try_inv(self) -> Option<Self> {
+        if self == Self::ZERO {
+            None
+        } else {
+            Some(self.inv())
+        }
+    }
+
+    /// Return the FieldElement divided by the other one, or a DivisionByZero
+    /// error if the other one is zero.
+    #[inline]
+    pub fn checked_div(self, other: FieldElement) -> Result<Self, FieldError> {
+        other.try_inv().map(|inv| self * inv).ok_or(FieldError::DivisionByZero)
+    }
+
     /// Returns the square of the FieldElement which is equivalent to multiplying the FieldElement by itself.
     pub fn square(&self) -> Self {
-        self.mul(*self)
+        *self * *self
     }
 
     /// Returns the cube of the FieldElement which is equivalent to multiplying the FieldElement by itself twice.
@@ -159,6 +427,101 @@ impl FieldElement {
         self.square().mul(*self)
     }
 
+    /// Returns the FieldElement raised to the power 7, the S-box exponent ALPHA
+    /// of the field, with the addition chain x^2, x^3, x^6, x^7, i.e. with 4
+    /// multiplications instead of the loop of `exp_u64`.
+    #[inline(always)]
+    pub fn pow7(self) -> Self {
+        let x3 = self.cube();
+        x3.square() * self
+    }
+
+    /// Return a primitive 2^n_log2-th root of unity.
+    ///
+    /// # Panics
+    /// Panics if `n_log2` is greater than TWO_ADICITY, since the field has no
+    /// roots of unity of such orders.
+    pub fn get_root_of_unity(n_log2: u32) -> Self {
+        assert!(
+            n_log2 <= Self::TWO_ADICITY,
+            "The field has no primitive 2^{n_log2}-th root of unity."
+        );
+
+        // Squaring a primitive 2^k-th root of unity gives a primitive 2^(k-1)-th one.
+        let mut root = Self::TWO_ADIC_ROOT_OF_UNITY;
+        for _ in n_log2..Self::TWO_ADICITY {
+            root = root.square();
+        }
+        root
+    }
+
+    /// Return the Legendre symbol of the FieldElement: 0 for zero, 1 for a non-zero
+    /// quadratic residue and -1 for a quadratic non-residue. By Euler's criterion,
+    /// it is a^((p-1)/2), which is always one of 0, 1 and -1.
+    pub fn legendre(self) -> i8 {
+        let symbol = self.exp_u64((PRIME - 1) >> 1);
+        if symbol == Self::ZERO {
+            0
+        } else if symbol == Self::ONE {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Return whether the FieldElement is a square, which includes zero.
+    pub fn is_quadratic_residue(self) -> bool {
+        self.legendre() >= 0
+    }
+
+    /// Return a square root of the FieldElement if it is a quadratic residue, and
+    /// None otherwise. The square root is computed with the Tonelli-Shanks
+    /// algorithm, making use of PRIME - 1 = 2^32 * (2^32 - 1).
+    ///
+    /// NOTE: If `r` is a square root then so is `-r`; no guarantee is made about
+    ///       which of the two is returned.
+    pub fn sqrt(self) -> Option<Self> {
+        // PRIME - 1 = 2^S * Q with Q odd.
+        const S: u32 = FieldElement::TWO_ADICITY;
+        const Q: u64 = (PRIME - 1) >> S;
+
+        match self.legendre() {
+            0 => return Some(Self::ZERO),
+            -1 => return None,
+            _ => {}
+        }
+
+        // Invariants of the loop below: r^2 = a * t, t^(2^(m-1)) = 1, and c is a
+        // primitive 2^m-th root of unity. Once t = 1, r is a square root of a.
+        let mut m = S;
+        let mut c = Self::TWO_ADIC_ROOT_OF_UNITY;
+        let mut t = self.exp_u64(Q);
+        let mut r = self.exp_u64((Q + 1) >> 1);
+
+        while t != Self::ONE {
+            // Find the least i such that t^(2^i) = 1. It is always less than m.
+            let mut i = 0;
+            let mut t_pow = t;
+            while t_pow != Self::ONE {
+                t_pow = t_pow.square();
+                i += 1;
+            }
+
+            // b = c^(2^(m - i - 1)), so that b^2 is a primitive 2^i-th root of unity.
+            let mut b = c;
+            for _ in 0..(m - i - 1) {
+                b = b.square();
+            }
+
+            m = i;
+            c = b.square();
+            t *= c;
+            r *= b;
+        }
+
+        Some(r)
+    }
+
     /// Serialize the FieldElement into a little-endian byte array of size 8.
     pub fn to_bytes(self) -> [u8; 8] {
         self.value.to_le_bytes()
@@ -166,12 +529,28 @@ impl FieldElement {
 
     /// Deserialize the FieldElement from a little-endian byte array of size 8.
     pub fn from_bytes(arr: &[u8; 8]) -> Result<Self, FieldError> {
-        let value = u64::from_le_bytes(*arr);
-        if value >= PRIME {
-            Err(FieldError::DeserializationError)
-        } else {
-            Ok(Self::new(value))
-        }
+        Self::from_canonical(u64::from_le_bytes(*arr))
+    }
+
+    /// Serialize the FieldElement into a big-endian byte array of size 8.
+    pub fn to_bytes_be(self) -> [u8; 8] {
+        self.value.to_be_bytes()
+    }
+
+    /// Deserialize the FieldElement from a big-endian byte array of size 8.
+    pub fn from_bytes_be(arr: &[u8; 8]) -> Result<Self, FieldError> {
+        Self::from_canonical(u64::from_be_bytes(*arr))
+    }
+
+    /// Map 16 bytes, read as a little-endian u128, to the FieldElement they are
+    /// congruent to. Unlike `from_bytes`, every byte array is accepted.
+    ///
+    /// For uniformly random bytes, the output is within statistical distance
+    /// PRIME / 2^128 < 2^-64 of the uniform distribution on the field, so this
+    /// is a deterministic map from bytes to the field with negligible bias,
+    /// e.g. for hashing to the field.
+    pub fn from_bytes_wide(bytes: &[u8; 16]) -> Self {
+        bytes_to_field_wide(bytes)
     }
 }
 
@@ -182,6 +561,36 @@ impl Display for FieldElement {
     }
 }
 
+/// Format the FieldElement as the 16 hexadecimal digits of its value, most
+/// significant first, i.e. of its big-endian `to_bytes_be` serialization, with
+/// a 0x prefix in the alternate form `{:#x}`.
+impl LowerHex for FieldElement {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        write!(f, "{:016x}", self.value())
+    }
+}
+
+/// Format the FieldElement as `LowerHex` does, with uppercase digits.
+impl UpperHex for FieldElement {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        write!(f, "{:016X}", self.value())
+    }
+}
+
+/// Implement the Default trait for FieldElement as the additive identity ZERO.
+impl Default for FieldElement {
+    #[inline]
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
 /// Implement the PartialEq trait for FieldElement.
 impl PartialEq for FieldElement {
     #[inline]
@@ -190,6 +599,34 @@ impl PartialEq for FieldElement {
     }
 }
 
+impl Eq for FieldElement {}
+
+/// Implement the PartialOrd and Ord traits for FieldElement. Elements are ordered
+/// by their canonical value, consistently with PartialEq, so ZERO is the least
+/// element and PRIME - 1 the greatest.
+impl PartialOrd for FieldElement {
+    #[inline]
+    fn partial_cmp(&self, other: &FieldElement) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FieldElement {
+    #[inline]
+    fn cmp(&self, other: &FieldElement) -> Ordering {
+        self.value().cmp(&other.value())
+    }
+}
+
+/// Implement the Hash trait for FieldElement by hashing the canonical value,
+/// consistently with PartialEq.
+impl Hash for FieldElement {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value().hash(state);
+    }
+}
+
 /// Implement Add, AddAssign, Div, DivAssign, Neg, Mul, MulAssign, Sub, SubAssign for
 /// FieldElements. These operations are performed modulo PRIME.
 impl Add for FieldElement {
@@ -221,10 +658,13 @@ impl Mul for FieldElement {
 
     #[inline]
     fn mul(self, other: FieldElement) -> FieldElement {
-        Self::new(reduce((self.value as u128) * (other.value as u128)))
+        Self::from_wide((self.value as u128) * (other.value as u128))
     }
 }
 
+/// NOTE: Like `inv`, division by zero is only caught in debug builds. In release
+///       builds, dividing by zero returns zero. Use `checked_div` when the
+///       divisor may be zero.
 impl Div for FieldElement {
     type Output = Self;
 
@@ -291,9 +731,272 @@ impl SubAssign for FieldElement {
     }
 }
 
+/// Implement Add, Sub, Mul for references to FieldElements, and AddAssign,
+/// SubAssign, MulAssign with a reference on the right-hand side, so that elements
+/// stored in arrays can be combined without dereferencing them first, e.g. with
+/// `acc += x` while iterating over a slice. FieldElement is Copy, so these
+/// delegate to the operations on values.
+impl Add<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn add(self, other: &FieldElement) -> FieldElement {
+        *self + *other
+    }
+}
+
+impl AddAssign<&FieldElement> for FieldElement {
+    #[inline]
+    fn add_assign(&mut self, other: &FieldElement) {
+        *self = *self + *other;
+    }
+}
+
+impl Mul<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn mul(self, other: &FieldElement) -> FieldElement {
+        *self * *other
+    }
+}
+
+impl MulAssign<&FieldElement> for FieldElement {
+    #[inline]
+    fn mul_assign(&mut self, other: &FieldElement) {
+        *self = *self * *other;
+    }
+}
+
+impl Sub<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn sub(self, other: &FieldElement) -> FieldElement {
+        *self - *other
+    }
+}
+
+impl SubAssign<&FieldElement> for FieldElement {
+    #[inline]
+    fn sub_assign(&mut self, other: &FieldElement) {
+        *self = *self - *other;
+    }
+}
+
+/// Implement Add, Sub, Mul with u64 operands on either side, e.g. `x * 3u64`
+/// or `1u64 - x`. The u64 is converted with `FieldElement::new`, so values
+/// greater than or equal to PRIME are reduced modulo PRIME.
+impl Add<u64> for FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn add(self, other: u64) -> FieldElement {
+        self + FieldElement::new(other)
+    }
+}
+
+impl Add<FieldElement> for u64 {
+    type Output = FieldElement;
+
+    #[inline]
+    fn add(self, other: FieldElement) -> FieldElement {
+        FieldElement::new(self) + other
+    }
+}
+
+impl Mul<u64> for FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn mul(self, other: u64) -> FieldElement {
+        self * FieldElement::new(other)
+    }
+}
+
+impl Mul<FieldElement> for u64 {
+    type Output = FieldElement;
+
+    #[inline]
+    fn mul(self, other: FieldElement) -> FieldElement {
+        FieldElement::new(self) * other
+    }
+}
+
+impl Sub<u64> for FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn sub(self, other: u64) -> FieldElement {
+        self - FieldElement::new(other)
+    }
+}
+
+impl Sub<FieldElement> for u64 {
+    type Output = FieldElement;
+
+    #[inline]
+    fn sub(self, other: FieldElement) -> FieldElement {
+        FieldElement::new(self) - other
+    }
+}
+
+/// Implement Sum and Product for iterators over FieldElements and over references
+/// to FieldElements. The sum of an empty iterator is ZERO and its product is ONE.
+impl Sum for FieldElement {
+    fn sum<I: Iterator<Item = FieldElement>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a FieldElement> for FieldElement {
+    fn sum<I: Iterator<Item = &'a FieldElement>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Product for FieldElement {
+    fn product<I: Iterator<Item = FieldElement>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a FieldElement> for FieldElement {
+    fn product<I: Iterator<Item = &'a FieldElement>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+// FIELD TRAIT
+// =============================================================================
+
+impl Field for FieldElement {
+    const ZERO: Self = Self::ZERO;
+    const ONE: Self = Self::ONE;
+    const ALPHA: u64 = ALPHA;
+    const ALPHA_INV: u64 = ALPHA_INV;
+
+    #[inline(always)]
+    fn from_u64(value: u64) -> Self {
+        FieldElement::new(value)
+    }
+
+    #[inline(always)]
+    fn inv(self) -> Self {
+        FieldElement::inv(self)
+    }
+
+    #[inline(always)]
+    fn exp_u64(self, power: u64) -> Self {
+        FieldElement::exp_u64(self, power)
+    }
+
+    #[inline(always)]
+    fn square(self) -> Self {
+        FieldElement::square(&self)
+    }
+
+    #[inline(always)]
+    fn inner_product(a: &[Self], b: &[Self]) -> Self {
+        inner_product(a, b)
+    }
+
+    #[inline(always)]
+    fn sbox<const WIDTH: usize>(state: &mut [Self; WIDTH]) {
+        if ALPHA == 7 {
+            pow7_state(state);
+        } else {
+            for element in state.iter_mut() {
+                *element = element.exp_u64(ALPHA);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn inv_sbox<const WIDTH: usize>(state: &mut [Self; WIDTH]) {
+        goldilocks_inv_sbox(state);
+    }
+}
+
+// SERIALIZATION
+// =============================================================================
+
+/// Serialize the FieldElement as its canonical value.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FieldElement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.value())
+    }
+}
+
+/// Deserialize the FieldElement from its canonical value. Values >= PRIME are
+/// rejected rather than reduced, with `from_canonical`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FieldElement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u64::deserialize(deserializer)?;
+        Self::from_canonical(value).map_err(serde::de::Error::custom)
+    }
+}
+
+// CONSTANT-TIME OPERATIONS
+// =============================================================================
+
+/// Compare the canonical values of two FieldElements in constant time.
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for FieldElement {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+/// Select one of two FieldElements in constant time.
+#[cfg(feature = "subtle")]
+impl subtle::ConditionallySelectable for FieldElement {
+    fn conditional_select(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+        FieldElement {
+            value: u64::conditional_select(&a.value, &b.value, choice),
+        }
+    }
+}
+
+// ZEROIZATION
+// =============================================================================
+
+/// Overwrite the value with 0, with a write the compiler cannot optimize away.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for FieldElement {
+    fn zeroize(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+// PROPERTY TESTING
+// =============================================================================
+
+/// Generate a FieldElement from arbitrary bytes, reducing 16 of them modulo
+/// PRIME as in `from_wide`, so that no input is rejected and the result is
+/// always canonical.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FieldElement {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(FieldElement::from_wide(u.arbitrary()?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <u128 as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
 // TYPE CONVERSIONS
 // =============================================================================
 
+impl From<u128> for FieldElement {
+    fn from(x: u128) -> Self {
+        Self::from_u128(x)
+    }
+}
+
 impl From<u64> for FieldElement {
     fn from(x: u64) -> Self {
         Self::new(x)
@@ -318,6 +1021,25 @@ impl From<u8> for FieldElement {
     }
 }
 
+impl From<i64> for FieldElement {
+    /// Map a negative x to PRIME - (|x| mod PRIME), i.e. to the field element -|x|.
+    fn from(x: i64) -> Self {
+        // unsigned_abs does not overflow for i64::MIN, unlike -x.
+        let magnitude = Self::new(x.unsigned_abs());
+        if x < 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl From<i32> for FieldElement {
+    fn from(x: i32) -> Self {
+        Self::from(x as i64)
+    }
+}
+
 // From<[u8;8]> is not implemented since it will have to be very
 // opinionated about the endianness of the bytes and perform
 // modulo PRIME silently.
@@ -330,13 +1052,323 @@ impl TryFrom<[u8; 8]> for FieldElement {
     }
 }
 
+/// Deserialize a FieldElement from a little-endian slice of bytes, which must
+/// be exactly 8 bytes long.
+impl TryFrom<&[u8]> for FieldElement {
+    type Error = FieldError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: &[u8; 8] = bytes.try_into().map_err(|_| FieldError::InvalidLength)?;
+        Self::from_bytes(bytes)
+    }
+}
+
+/// Parse a FieldElement from its decimal representation, as written by Display.
+/// Values >= PRIME are rejected rather than reduced.
+impl FromStr for FieldElement {
+    type Err = FieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.parse::<u64>().map_err(|_| FieldError::ParseError)?;
+        Self::from_canonical(value)
+    }
+}
+
+// BATCH INVERSION
+// =============================================================================
+
+/// Return the inverses of all the given field elements using Montgomery's batch
+/// inversion trick, which costs a single `inv` plus 3 multiplications per element
+/// instead of one `inv` per element.
+///
+/// Zeros have no inverse, so they are skipped and left as zero in the output.
+pub fn batch_inv(elements: &[FieldElement]) -> Vec<FieldElement> {
+    let mut result = elements.to_vec();
+    batch_inv_mut(&mut result);
+    result
+}
+
+/// Replace each of the given field elements with its inverse in place. Like
+/// `batch_inv`, zeros are skipped and left as zero.
+pub fn batch_inv_mut(elements: &mut [FieldElement]) {
+    // Compute the running products of all the non-zero elements, such that
+    // products[i] = elements[0] * elements[1] * ... * elements[i].
+    let mut products = Vec::with_capacity(elements.len());
+    let mut acc = FieldElement::ONE;
+    for &element in elements.iter() {
+        if element != FieldElement::ZERO {
+            acc *= element;
+        }
+        products.push(acc);
+    }
+
+    // Invert the product of all the elements once. This is the only inversion.
+    let mut acc_inv = acc.inv();
+
+    // Walk back through the elements. At each step acc_inv is the inverse of
+    // products[i], so multiplying it by products[i - 1] leaves the inverse of
+    // elements[i], and multiplying it by elements[i] gives the inverse of
+    // products[i - 1] for the next step.
+    for i in (0..elements.len()).rev() {
+        let element = elements[i];
+        if element == FieldElement::ZERO {
+            continue;
+        }
+
+        let prev = if i == 0 { FieldElement::ONE } else { products[i - 1] };
+        elements[i] = acc_inv * prev;
+        acc_inv *= element;
+    }
+}
+
+// BATCH ARITHMETIC
+// =============================================================================
+
+/// Write a[i] + b[i] into out[i] for every i.
+///
+/// With the `simd` feature on x86_64 targets compiled with AVX2 support, e.g.
+/// with `-C target-cpu=native`, 4 elements are added at a time. The results are
+/// the same as with the scalar loop used otherwise.
+///
+/// # Panics
+/// Panics if a, b and out do not all have the same length.
+pub fn add_batch(a: &[FieldElement], b: &[FieldElement], out: &mut [FieldElement]) {
+    check_batch_lengths(a, b, out);
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "avx2"))]
+    let start = avx2::add_batch(a, b, out);
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64", target_feature = "avx2")))]
+    let start = 0;
+
+    for ((x, y), z) in a[start..].iter().zip(b[start..].iter()).zip(out[start..].iter_mut()) {
+        *z = *x + *y;
+    }
+}
+
+/// Write a[i] * b[i] into out[i] for every i.
+///
+/// With the `simd` feature on x86_64 targets compiled with AVX2 support, 4
+/// elements are multiplied at a time, see `add_batch`.
+///
+/// # Panics
+/// Panics if a, b and out do not all have the same length.
+pub fn mul_batch(a: &[FieldElement], b: &[FieldElement], out: &mut [FieldElement]) {
+    check_batch_lengths(a, b, out);
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "avx2"))]
+    let start = avx2::mul_batch(a, b, out);
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64", target_feature = "avx2")))]
+    let start = 0;
+
+    for ((x, y), z) in a[start..].iter().zip(b[start..].iter()).zip(out[start..].iter_mut()) {
+        *z = *x * *y;
+    }
+}
+
+/// Raise every element of the state to the power 7 in place, with the addition
+/// chain of `pow7` applied to all the elements at once: each step of the chain
+/// is a `mul_batch` over the whole state, so that the multiplications of the
+/// different elements are independent and can overlap, or run 4 at a time with
+/// the `simd` feature. The result is the same as `pow7` on every element.
+pub fn pow7_state<const WIDTH: usize>(state: &mut [FieldElement; WIDTH]) {
+    let mut x2 = [FieldElement::ZERO; WIDTH];
+    let mut x3 = [FieldElement::ZERO; WIDTH];
+    let mut x6 = [FieldElement::ZERO; WIDTH];
+    mul_batch(state, state, &mut x2);
+    mul_batch(&x2, state, &mut x3);
+    mul_batch(&x3, &x3, &mut x6);
+
+    let x = *state;
+    mul_batch(&x6, &x, state);
+}
+
+/// Raise every element of the state to the power ALPHA_INV using 72
+/// multiplications per element, instead of the 95 that square-and-multiply
+/// needs for this 64-bit exponent.
+///
+/// In binary, ALPHA_INV = 0b1001001001001001001001001001000110110110110110110110110110110111,
+/// i.e. the repeating pattern 100 followed by 110 and a final 111, which the
+/// addition chain below builds up. Each step is applied to the whole state at
+/// once, so that the multiplications of different elements, which do not depend
+/// on each other, can be pipelined.
+///
+/// This is the inverse S-box layer of `FieldElement`, see `Field::inv_sbox`.
+#[inline(always)]
+fn goldilocks_inv_sbox<const WIDTH: usize>(state: &mut [FieldElement; WIDTH]) {
+    // base^10
+    let t1 = square_state(state);
+    // base^100
+    let t2 = square_state(&t1);
+    // base^100100
+    let t3 = exp_acc_state::<WIDTH, 3>(&t2, &t2);
+    // base^100100100100
+    let t4 = exp_acc_state::<WIDTH, 6>(&t3, &t3);
+    // base^100100100100100100100100
+    let t5 = exp_acc_state::<WIDTH, 12>(&t4, &t4);
+    // base^100100100100100100100100100100
+    let t6 = exp_acc_state::<WIDTH, 6>(&t5, &t3);
+    // base^1001001001001001001001001001000100100100100100100100100100100
+    let t7 = exp_acc_state::<WIDTH, 31>(&t6, &t6);
+    // base^1001001001001001001001001001000110110110110110110110110110110111
+    let a = exp_acc_state::<WIDTH, 1>(&t7, &t6);
+    let a = square_state(&square_state(&a));
+    for (i, element) in state.iter_mut().enumerate() {
+        *element *= a[i] * t1[i] * t2[i];
+    }
+}
+
+/// Return the element-wise square of the state.
+#[inline(always)]
+fn square_state<const WIDTH: usize>(state: &[FieldElement; WIDTH]) -> [FieldElement; WIDTH] {
+    let mut result = *state;
+    for element in result.iter_mut() {
+        *element = element.square();
+    }
+    result
+}
+
+/// Square every element of the base N times and multiply it by the matching
+/// element of the tail, as `exp_acc` does for a single element.
+#[inline(always)]
+fn exp_acc_state<const WIDTH: usize, const N: usize>(
+    base: &[FieldElement; WIDTH],
+    tail: &[FieldElement; WIDTH],
+) -> [FieldElement; WIDTH] {
+    let mut result = *base;
+    for _ in 0..N {
+        result = square_state(&result);
+    }
+    for (element, &t) in result.iter_mut().zip(tail.iter()) {
+        *element *= t;
+    }
+    result
+}
+
+// BATCH SERIALIZATION
+// =============================================================================
+
+/// Map at most 16 bytes to the FieldElement congruent to the integer they encode
+/// in little-endian order, i.e. the sum of bytes[i] * 256^i.
+///
+/// This is the byte order of `to_bytes`, so that for every element x,
+/// `bytes_to_field_wide(&x.to_bytes()) == x`. The same order is used to reduce
+/// the chunks of pseudorandom bytes into round constants, and to pack bytes into
+/// field elements in `hash_bytes`, so all of them must change together.
+///
+/// # Panics
+/// Panics if there are more than 16 bytes.
+pub fn bytes_to_field_wide(bytes: &[u8]) -> FieldElement {
+    assert!(bytes.len() <= 16, "At most 16 bytes fit in the u128 that is reduced.");
+    let mut buf = [0u8; 16];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    FieldElement::from_wide(u128::from_le_bytes(buf))
+}
+
+/// Serialize the field elements into bytes, as the concatenation of their
+/// little-endian `to_bytes` serializations.
+pub fn elements_to_bytes(elements: &[FieldElement]) -> Vec<u8> {
+    elements.iter().flat_map(|element| element.to_bytes()).collect()
+}
+
+/// Serialize the field elements into the start of out as `elements_to_bytes`
+/// does, without allocating, and return the number of bytes written, i.e. 8 per
+/// element. Return an InvalidLength error, leaving out untouched, if out is too
+/// small to hold them. The bytes of out past the serialization are left as is.
+pub fn write_bytes(elements: &[FieldElement], out: &mut [u8]) -> Result<usize, FieldError> {
+    let len = elements.len() * 8;
+    if out.len() < len {
+        return Err(FieldError::InvalidLength);
+    }
+    for (chunk, element) in out[..len].chunks_exact_mut(8).zip(elements.iter()) {
+        chunk.copy_from_slice(&element.to_bytes());
+    }
+    Ok(len)
+}
+
+/// Deserialize field elements from bytes written by `elements_to_bytes`, i.e.
+/// from consecutive little-endian 8-byte chunks. Return an InvalidLength error
+/// if the length of the bytes is not a multiple of 8, and a NonCanonical error
+/// if any of the values is not canonical, i.e. >= PRIME.
+pub fn bytes_to_elements(bytes: &[u8]) -> Result<Vec<FieldElement>, FieldError> {
+    let chunks = bytes.chunks_exact(8);
+    if !chunks.remainder().is_empty() {
+        return Err(FieldError::InvalidLength);
+    }
+    chunks.map(FieldElement::try_from).collect()
+}
+
+// INNER PRODUCTS
+// =============================================================================
+
+/// Return acc + a * b with a single reduction. This cannot overflow, since
+/// (p - 1) + (p - 1)^2 = p * (p - 1) < 2^128.
+#[inline]
+pub fn mul_acc(acc: FieldElement, a: FieldElement, b: FieldElement) -> FieldElement {
+    let sum = (acc.value as u128) + (a.value as u128) * (b.value as u128);
+    FieldElement::from_wide(sum)
+}
+
+/// Return the inner product of a and b, i.e. the sum of a[i] * b[i], with a
+/// constant number of reductions instead of one per product, see
+/// `FieldElementUnreduced`.
+///
+/// # Panics
+/// Panics if a and b have different lengths.
+pub fn inner_product(a: &[FieldElement], b: &[FieldElement]) -> FieldElement {
+    assert_eq!(a.len(), b.len(), "The inner product needs slices of the same length.");
+
+    let mut acc = FieldElementUnreduced::ZERO;
+    for (&x, &y) in a.iter().zip(b.iter()) {
+        acc.add_product(x, y);
+    }
+    acc.reduce_full()
+}
+
+/// A sum of products of field elements that has not been reduced yet.
+///
+/// Every product is split into its low and high 64 bits, which are summed into
+/// two separate u128 accumulators with plain integer additions. Each of them
+/// grows by less than 2^64 per product, so they cannot overflow for fewer than
+/// 2^63 products, and the sum is only reduced once by `reduce_full`.
+#[derive(Clone, Copy, Debug)]
+struct FieldElementUnreduced {
+    low: u128,
+    high: u128,
+}
+
+impl FieldElementUnreduced {
+    /// The empty sum.
+    const ZERO: Self = Self { low: 0, high: 0 };
+
+    /// Add a * b to the sum without reducing it.
+    #[inline(always)]
+    fn add_product(&mut self, a: FieldElement, b: FieldElement) {
+        let product = (a.value as u128) * (b.value as u128);
+        self.low += product as u64 as u128;
+        self.high += product >> 64;
+    }
+
+    /// Return the canonical field element congruent to the sum.
+    ///
+    /// The sum is low + 2^64 * high, where 2^64 = 2^32 - 1 (mod p). Once high is
+    /// reduced below 2^64, high * (2^32 - 1) is less than 2^96, and adding it to
+    /// low, which is less than 2^127, cannot overflow, so a single reduction of
+    /// the result is enough.
+    #[inline(always)]
+    fn reduce_full(self) -> FieldElement {
+        let high = reduce(self.high) as u128;
+        FieldElement::from_wide(self.low + (high << 32) - high)
+    }
+}
+
 // HELPER FUNCTIONS
 // =============================================================================
 
 /// This function reduces a 128-bit number modulo PRIME, based on the instructions at the link below.
+/// The result is congruent to x but may not be canonical, see `FieldElement::from_wide`.
 /// https://cp4space.hatsya.com/2021/09/01/an-efficient-prime-for-number-theoretic-transforms/
 #[inline]
-fn reduce(x: u128) -> u64 {
+pub(crate) const fn reduce(x: u128) -> u64 {
     // Split the 128-bit number into 3 parts, such that the number can be written as follows.
     // x = low + 2^64 * middle + 2^96 * high
     let low: u64 = x as u64; // low 64 bits
@@ -363,6 +1395,10 @@ fn reduce(x: u128) -> u64 {
     // 2^64 = p + 2^32 - 1, or that 2^64 ≡ 2^32 - 1
     // Replace 2^64 with this value in the equation for x.
     // x ≡ low - high + (2^32 - 1) * middle
+    // This cannot underflow, nor overflow the shift, only because middle holds
+    // the 32 bits 64..96 of x: then middle << 32 is computed exactly and is at
+    // least middle. Any change to the split above must preserve this.
+    debug_assert!(middle <= u32::MAX as u64, "middle must fit in 32 bits");
     let product = (middle << 32) - middle;
 
     // Add the product to low - high.
@@ -372,6 +1408,50 @@ fn reduce(x: u128) -> u64 {
     result.wrapping_sub((over as u64) * PRIME)
 }
 
+/// The inverse of PRIME modulo 2^64, used by the Montgomery reduction. Since
+/// PRIME = 1 - 2^32 (mod 2^64), its inverse is 1 + 2^32.
+const PRIME_INV_MOD_R: u64 = (1 << 32) + 1;
+
+const _: () = assert!(PRIME.wrapping_mul(PRIME_INV_MOD_R) == 1);
+
+/// Return x * 2^-64 mod PRIME in canonical form, with the Montgomery reduction
+/// for R = 2^64. x must be less than PRIME * 2^64.
+///
+/// With m = x * PRIME^-1 mod 2^64, x - m * PRIME is divisible by 2^64, and the
+/// low 64 bits of both terms cancel out, so the quotient is the difference of
+/// their high 64 bits. Both are less than PRIME, so adding PRIME on a borrow
+/// brings the difference into 0..PRIME.
+#[inline(always)]
+fn montgomery_reduce(x: u128) -> u64 {
+    let (low, high) = (x as u64, (x >> 64) as u64);
+    debug_assert!(high < PRIME, "the input must be less than PRIME * 2^64");
+
+    let m = low.wrapping_mul(PRIME_INV_MOD_R);
+    let m_prime_high = (((m as u128) * (PRIME as u128)) >> 64) as u64;
+    let (result, borrow) = high.overflowing_sub(m_prime_high);
+    result.wrapping_add((borrow as u64) * PRIME)
+}
+
+/// Panics unless the inputs and the output of a batch operation all have the
+/// same length.
+#[inline(always)]
+fn check_batch_lengths(a: &[FieldElement], b: &[FieldElement], out: &[FieldElement]) {
+    assert!(
+        a.len() == b.len() && a.len() == out.len(),
+        "The inputs and the output of a batch operation must have the same length."
+    );
+}
+
+/// Swaps a and b if `bit` is 1 and leaves them unchanged if it is 0, without
+/// branching on `bit`.
+#[inline(always)]
+fn conditional_swap(a: &mut FieldElement, b: &mut FieldElement, bit: u64) {
+    let mask = 0u64.wrapping_sub(bit);
+    let t = mask & (a.value ^ b.value);
+    a.value ^= t;
+    b.value ^= t;
+}
+
 /// Squares the base N number of times and multiplies the result by the tail value.
 #[inline(always)]
 fn exp_acc<const N: usize>(base: FieldElement, tail: FieldElement) -> FieldElement {
@@ -1,5 +1,13 @@
-use super::{FieldElement, ONE, PRIME, ZERO};
-use crate::utils::errors::FieldError;
+use super::{
+    add_batch, batch_inv, batch_inv_mut, bytes_to_elements, bytes_to_field_wide, elements_to_bytes,
+    inner_product, mul_acc, mul_batch, pow7_state, reduce, write_bytes, FieldElement,
+    FieldElementUnreduced, PRIME,
+};
+use crate::utils::{
+    errors::FieldError,
+    test_utils::{test_array, test_elements, test_rng},
+};
+use rand::Rng;
 
 #[test]
 fn test_addition() {
@@ -13,7 +21,7 @@ fn test_addition() {
 
     // ------ Test addition with field overflow -----------------------------------------
 
-    let a = FieldElement::new(PRIME) - ONE;
+    let a = FieldElement::new(PRIME) - FieldElement::ONE;
     let b = FieldElement::new(25);
 
     let result = a + b;
@@ -53,15 +61,15 @@ fn test_negation() {
 
     let result = -a;
     assert_eq!(result.value, PRIME - 15);
-    assert_eq!(a + result, ZERO);
+    assert_eq!(a + result, FieldElement::ZERO);
 }
 
 #[test]
 fn test_mul() {
     // test multiplication by zero and one
     let r: FieldElement = FieldElement::new(5);
-    assert_eq!(ZERO, r * ZERO);
-    assert_eq!(r, r * ONE);
+    assert_eq!(FieldElement::ZERO, r * FieldElement::ZERO);
+    assert_eq!(r, r * FieldElement::ONE);
 
     // test basic multiplication
     assert_eq!(FieldElement::from(15u8), FieldElement::from(5u8) * FieldElement::from(3u8));
@@ -69,19 +77,19 @@ fn test_mul() {
     // test multiplication which is guaranted to overflow
     let m = PRIME;
     let t = FieldElement::from(m - 1);
-    assert_eq!(ONE, t * t);
+    assert_eq!(FieldElement::ONE, t * t);
     assert_eq!(FieldElement::from(m - 2), t * FieldElement::from(2u8));
     assert_eq!(FieldElement::from(m - 4), t * FieldElement::from(4u8));
 
-    let t = (m + 1) / 2;
-    assert_eq!(ONE, FieldElement::from(t) * FieldElement::from(2u8));
+    let t = m.div_ceil(2);
+    assert_eq!(FieldElement::ONE, FieldElement::from(t) * FieldElement::from(2u8));
 }
 
 #[test]
 fn inv() {
     // --------- test inverse of identity elements ----------------------------
 
-    assert_eq!(ONE, ONE.inv());
+    assert_eq!(FieldElement::ONE, FieldElement::ONE.inv());
 
     // --------- test inverses of field elements -------------------------------
 
@@ -91,14 +99,14 @@ fn inv() {
 
 #[test]
 fn exp() {
-    let a = ZERO;
-    assert_eq!(a.exp(ZERO), ONE);
-    assert_eq!(a.exp(ONE), ZERO);
+    let a = FieldElement::ZERO;
+    assert_eq!(a.exp(FieldElement::ZERO), FieldElement::ONE);
+    assert_eq!(a.exp(FieldElement::ONE), FieldElement::ZERO);
 
-    let a = ONE;
-    assert_eq!(a.exp(ZERO), ONE);
-    assert_eq!(a.exp(ONE), ONE);
-    assert_eq!(a.exp(FieldElement { value: 3 }), ONE);
+    let a = FieldElement::ONE;
+    assert_eq!(a.exp(FieldElement::ZERO), FieldElement::ONE);
+    assert_eq!(a.exp(FieldElement::ONE), FieldElement::ONE);
+    assert_eq!(a.exp(FieldElement { value: 3 }), FieldElement::ONE);
 
     let a: FieldElement = FieldElement { value: 69 };
     assert_eq!(a.exp(FieldElement::from(6u8)), a * a * a * a * a * a);
@@ -127,10 +135,10 @@ fn test_double() {
 
 #[test]
 fn test_to_bytes() {
-    let r: FieldElement = ZERO;
+    let r: FieldElement = FieldElement::ZERO;
     assert_eq!(r.to_bytes(), [0u8; 8]);
 
-    let r: FieldElement = ONE;
+    let r: FieldElement = FieldElement::ONE;
     assert_eq!(r.to_bytes(), [1, 0, 0, 0, 0, 0, 0, 0]);
 
     let r: FieldElement = FieldElement::new(PRIME - 1);
@@ -142,17 +150,916 @@ fn test_from_bytes() {
     let bytes = [0, 0, 0, 0, 255, 255, 255, 255];
     match FieldElement::from_bytes(&bytes) {
         Ok(fe) => assert_eq!(fe, FieldElement::new(PRIME - 1)),
-        Err(_) => assert!(false),
+        Err(_) => panic!("expected a canonical value to deserialize"),
     }
 
     let bytes = [1, 0, 0, 0, 255, 255, 255, 255];
     match FieldElement::from_bytes(&bytes) {
-        Ok(_) => assert!(false),
-        Err(e) => assert_eq!(e, FieldError::DeserializationError),
+        Ok(_) => panic!("expected a non-canonical value to be rejected"),
+        Err(e) => assert_eq!(e, FieldError::NonCanonical),
     }
 }
 
+#[test]
+fn test_to_bytes_be() {
+    assert_eq!(FieldElement::ZERO.to_bytes_be(), [0u8; 8]);
+    assert_eq!(FieldElement::ONE.to_bytes_be(), [0, 0, 0, 0, 0, 0, 0, 1]);
+
+    let r: FieldElement = FieldElement::new(PRIME - 1);
+    assert_eq!(r.to_bytes_be(), [255, 255, 255, 255, 0, 0, 0, 0]);
+    assert_ne!(r.to_bytes_be(), r.to_bytes());
+}
+
+#[test]
+fn test_from_bytes_be() {
+    let bytes = [255, 255, 255, 255, 0, 0, 0, 0];
+    assert_eq!(FieldElement::from_bytes_be(&bytes), Ok(FieldElement::new(PRIME - 1)));
+
+    let bytes = [255, 255, 255, 255, 0, 0, 0, 1];
+    assert_eq!(FieldElement::from_bytes_be(&bytes), Err(FieldError::NonCanonical));
+
+    // round-trip
+    for value in [0, 1, 255, 256, 0x0123456789ABCDEF, PRIME - 1] {
+        let r = FieldElement::new(value);
+        assert_eq!(FieldElement::from_bytes_be(&r.to_bytes_be()), Ok(r));
+        assert_eq!(FieldElement::from_bytes(&r.to_bytes()), Ok(r));
+    }
+}
+
+#[test]
+fn test_from_canonical() {
+    assert_eq!(FieldElement::from_canonical(0), Ok(FieldElement::ZERO));
+    assert_eq!(FieldElement::from_canonical(12345), Ok(FieldElement::new(12345)));
+    assert_eq!(FieldElement::from_canonical(PRIME - 1), Ok(FieldElement::new(PRIME - 1)));
+
+    assert_eq!(FieldElement::from_canonical(PRIME), Err(FieldError::NonCanonical));
+    assert_eq!(FieldElement::from_canonical(PRIME + 5), Err(FieldError::NonCanonical));
+    assert_eq!(FieldElement::from_canonical(u64::MAX), Err(FieldError::NonCanonical));
+}
+
+#[test]
+fn test_from_signed() {
+    assert_eq!(FieldElement::from(-1i64), FieldElement::new(PRIME - 1));
+    assert_eq!(FieldElement::from(-3i64), FieldElement::new(PRIME - 3));
+    assert_eq!(FieldElement::from(0i64), FieldElement::ZERO);
+    assert_eq!(FieldElement::from(i64::MAX), FieldElement::new(i64::MAX as u64));
+    assert_eq!(FieldElement::from(i64::MIN), FieldElement::new(PRIME - (1u64 << 63)));
+    assert_eq!(FieldElement::from(i64::MIN) + FieldElement::new(1u64 << 63), FieldElement::ZERO);
+
+    assert_eq!(FieldElement::from(-1i32), FieldElement::new(PRIME - 1));
+    assert_eq!(FieldElement::from(i32::MIN), FieldElement::new(PRIME - (1u64 << 31)));
+    assert_eq!(FieldElement::from(i32::MAX), FieldElement::from(i32::MAX as u32));
+}
+
+#[test]
+fn test_from_str() {
+    assert_eq!("12345".parse::<FieldElement>(), Ok(FieldElement::new(12345)));
+    assert_eq!((PRIME - 1).to_string().parse(), Ok(FieldElement::new(PRIME - 1)));
+
+    assert_eq!(PRIME.to_string().parse::<FieldElement>(), Err(FieldError::NonCanonical));
+    assert_eq!(u64::MAX.to_string().parse::<FieldElement>(), Err(FieldError::NonCanonical));
+    assert_eq!("99999999999999999999".parse::<FieldElement>(), Err(FieldError::ParseError));
+    assert_eq!("0x10".parse::<FieldElement>(), Err(FieldError::ParseError));
+    assert_eq!("-1".parse::<FieldElement>(), Err(FieldError::ParseError));
+    assert_eq!("".parse::<FieldElement>(), Err(FieldError::ParseError));
+
+    // round-trip with Display
+    for r in test_elements(16, 0) {
+        assert_eq!(r.to_string().parse(), Ok(r));
+    }
+}
+
+#[test]
+fn test_elements_to_bytes() {
+    let elements = test_elements(10, 1);
+    let bytes = elements_to_bytes(&elements);
+    assert_eq!(bytes.len(), 80);
+    assert_eq!(bytes[8..16], elements[1].to_bytes());
+    assert_eq!(bytes_to_elements(&bytes), Ok(elements));
+
+    assert_eq!(elements_to_bytes(&[]), Vec::<u8>::new());
+    assert_eq!(bytes_to_elements(&[]), Ok(Vec::new()));
+
+    // the length must be a multiple of 8
+    assert_eq!(bytes_to_elements(&bytes[..79]), Err(FieldError::InvalidLength));
+    assert_eq!(bytes_to_elements(&[0; 3]), Err(FieldError::InvalidLength));
+
+    // and every value must be canonical
+    let mut bytes = bytes;
+    bytes[72..].copy_from_slice(&PRIME.to_le_bytes());
+    assert_eq!(bytes_to_elements(&bytes), Err(FieldError::NonCanonical));
+}
+
 #[test]
 fn test_try_from() {
-    test_from_bytes();
+    let bytes = [0, 0, 0, 0, 255, 255, 255, 255];
+    assert_eq!(FieldElement::try_from(bytes), Ok(FieldElement::new(PRIME - 1)));
+
+    let bytes = [1, 0, 0, 0, 255, 255, 255, 255];
+    assert_eq!(FieldElement::try_from(bytes), Err(FieldError::NonCanonical));
+}
+
+#[test]
+fn test_try_from_slice() {
+    let bytes: &[u8] = &[0, 0, 0, 0, 255, 255, 255, 255, 0];
+    assert_eq!(FieldElement::try_from(&bytes[..8]), Ok(FieldElement::new(PRIME - 1)));
+    assert_eq!(FieldElement::try_from(&bytes[..7]), Err(FieldError::InvalidLength));
+    assert_eq!(FieldElement::try_from(bytes), Err(FieldError::InvalidLength));
+    assert_eq!(FieldElement::try_from(&bytes[..0]), Err(FieldError::InvalidLength));
+
+    let bytes: &[u8] = &[1, 0, 0, 0, 255, 255, 255, 255];
+    assert_eq!(FieldElement::try_from(bytes), Err(FieldError::NonCanonical));
+}
+
+#[test]
+fn exp_u64() {
+    let a = FieldElement::ZERO;
+    assert_eq!(a.exp_u64(0), FieldElement::ONE);
+    assert_eq!(a.exp_u64(1), FieldElement::ZERO);
+
+    let a: FieldElement = FieldElement { value: 69 };
+    assert_eq!(a.exp_u64(6), a.exp(FieldElement::from(6u8)));
+
+    // by Fermat's little theorem, a^p = a and a^(p-1) = 1 for non-zero a
+    assert_eq!(a.exp_u64(PRIME), a);
+    assert_eq!(a.exp_u64(PRIME - 1), FieldElement::ONE);
+    assert_eq!(a.exp_u64(u64::MAX), a.exp_u64(u64::MAX - (PRIME - 1)));
+
+    // the field element form of the exponent reduces it modulo PRIME
+    assert_eq!(a.exp(FieldElement::new(PRIME)), FieldElement::ONE);
+}
+
+#[test]
+fn test_batch_inv() {
+    // a deterministic sequence of distinct non-zero elements spread across the field
+    let elements = test_elements(16, 2);
+    assert!(!elements.contains(&FieldElement::ZERO));
+
+    let expected: Vec<FieldElement> = elements.iter().map(|e| e.inv()).collect();
+    assert_eq!(batch_inv(&elements), expected);
+
+    let mut in_place = elements.clone();
+    batch_inv_mut(&mut in_place);
+    assert_eq!(in_place, expected);
+
+    // --------- zeros are skipped and left as zero ------------------------------
+
+    let mut elements = elements;
+    elements[0] = FieldElement::ZERO;
+    elements[7] = FieldElement::ZERO;
+    elements[15] = FieldElement::ZERO;
+
+    let result = batch_inv(&elements);
+    for (element, inverse) in elements.iter().zip(result.iter()) {
+        if *element == FieldElement::ZERO {
+            assert_eq!(*inverse, FieldElement::ZERO);
+        } else {
+            assert_eq!(*inverse, element.inv());
+        }
+    }
+
+    // --------- edge cases --------------------------------------------------------
+
+    assert!(batch_inv(&[]).is_empty());
+    assert_eq!(batch_inv(&[FieldElement::ZERO]), vec![FieldElement::ZERO]);
+}
+
+#[test]
+fn test_sqrt() {
+    assert_eq!(FieldElement::ZERO.sqrt(), Some(FieldElement::ZERO));
+    assert_eq!(FieldElement::ONE.sqrt().map(|r| r.square()), Some(FieldElement::ONE));
+
+    // --------- round-trip squares through sqrt --------------------------------
+
+    for x in test_elements(32, 3) {
+        let square = x.square();
+        let root = square.sqrt().expect("a square must have a square root");
+        assert_eq!(root.square(), square);
+        assert!(root == x || root == -x);
+    }
+
+    // --------- non-residues have no square root --------------------------------
+
+    assert!(FieldElement::QUADRATIC_NON_RESIDUE.sqrt().is_none());
+    let x = FieldElement::new(12345);
+    assert!((x.square() * FieldElement::QUADRATIC_NON_RESIDUE).sqrt().is_none());
+}
+
+#[test]
+fn test_generator() {
+    // the generator has order exactly PRIME - 1, where PRIME - 1 = 2^32 * 3 * 5 * 17 * 257 * 65537
+    let g = FieldElement::GENERATOR;
+    assert_eq!(g.exp_u64(PRIME - 1), FieldElement::ONE);
+    for factor in [2, 3, 5, 17, 257, 65537] {
+        assert_ne!(g.exp_u64((PRIME - 1) / factor), FieldElement::ONE);
+    }
+
+    assert_eq!(
+        FieldElement::TWO_ADIC_ROOT_OF_UNITY,
+        g.exp_u64((PRIME - 1) >> FieldElement::TWO_ADICITY)
+    );
+}
+
+#[test]
+fn test_get_root_of_unity() {
+    assert_eq!(FieldElement::get_root_of_unity(0), FieldElement::ONE);
+    assert_eq!(FieldElement::get_root_of_unity(1), -FieldElement::ONE);
+
+    for k in 1..=FieldElement::TWO_ADICITY {
+        let root = FieldElement::get_root_of_unity(k);
+        assert_eq!(root.exp_u64(1 << k), FieldElement::ONE);
+        assert_ne!(root.exp_u64(1 << (k - 1)), FieldElement::ONE);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_get_root_of_unity_too_large() {
+    FieldElement::get_root_of_unity(FieldElement::TWO_ADICITY + 1);
+}
+
+#[test]
+fn test_error() {
+    fn parse(bytes: [u8; 8]) -> Result<FieldElement, Box<dyn std::error::Error>> {
+        Ok(FieldElement::try_from(bytes)?)
+    }
+
+    assert!(parse([0; 8]).is_ok());
+
+    let err = parse([255; 8]).unwrap_err();
+    assert_eq!(err.to_string(), FieldError::NonCanonical.to_string());
+}
+
+#[test]
+fn test_sum() {
+    let empty: [FieldElement; 0] = [];
+    assert_eq!(empty.iter().sum::<FieldElement>(), FieldElement::ZERO);
+    assert_eq!(empty.into_iter().sum::<FieldElement>(), FieldElement::ZERO);
+
+    let single = [FieldElement::new(42)];
+    assert_eq!(single.iter().sum::<FieldElement>(), FieldElement::new(42));
+
+    let elements = [FieldElement::new(PRIME - 1), FieldElement::new(5), FieldElement::new(10)];
+    assert_eq!(elements.iter().sum::<FieldElement>(), FieldElement::new(14));
+    assert_eq!(elements.into_iter().sum::<FieldElement>(), FieldElement::new(14));
+
+    // dot product of two rows
+    let a = [1u64, 2, 3].map(FieldElement::new);
+    let b = [4u64, 5, 6].map(FieldElement::new);
+    let dot: FieldElement = a.iter().zip(b.iter()).map(|(a, b)| *a * *b).sum();
+    assert_eq!(dot, FieldElement::new(32));
+}
+
+#[test]
+fn test_product() {
+    let empty: [FieldElement; 0] = [];
+    assert_eq!(empty.iter().product::<FieldElement>(), FieldElement::ONE);
+    assert_eq!(empty.into_iter().product::<FieldElement>(), FieldElement::ONE);
+
+    let single = [FieldElement::new(42)];
+    assert_eq!(single.iter().product::<FieldElement>(), FieldElement::new(42));
+
+    let elements = [FieldElement::new(PRIME - 1), FieldElement::new(5), FieldElement::new(10)];
+    assert_eq!(elements.iter().product::<FieldElement>(), -FieldElement::new(50));
+    assert_eq!(elements.into_iter().product::<FieldElement>(), -FieldElement::new(50));
+}
+
+#[test]
+fn test_reference_operators() {
+    let a = [PRIME - 1, 5, 10].map(FieldElement::new);
+    let b = [3u64, PRIME - 2, 7].map(FieldElement::new);
+
+    for (x, y) in a.iter().zip(b.iter()) {
+        assert_eq!(x + y, *x + *y);
+        assert_eq!(x - y, *x - *y);
+        assert_eq!(x * y, *x * *y);
+        assert_eq!(x * y + x * y, (*x * *y).double());
+    }
+
+    let mut sum = FieldElement::ZERO;
+    let mut product = FieldElement::ONE;
+    for x in &a {
+        sum += x;
+        product *= x;
+    }
+    assert_eq!(sum, a.iter().sum());
+    assert_eq!(product, a.iter().product());
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_random() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(42);
+
+    const SAMPLES: usize = 10_000;
+    let mut odd = 0;
+    for _ in 0..SAMPLES {
+        let x = FieldElement::random(&mut rng);
+        assert!(x.value() < PRIME);
+        odd += (x.value() & 1) as usize;
+    }
+
+    // the low bit should be roughly balanced; this bound is over 10 standard deviations
+    assert!(odd.abs_diff(SAMPLES / 2) < 500);
+
+    // different seeds give different elements
+    let a = FieldElement::random(&mut StdRng::seed_from_u64(1));
+    let b = FieldElement::random(&mut StdRng::seed_from_u64(2));
+    assert_ne!(a, b);
+}
+
+#[test]
+fn test_ordering() {
+    assert!(FieldElement::ZERO < FieldElement::ONE);
+    assert!(FieldElement::new(PRIME - 1) > FieldElement::new(PRIME - 2));
+    // the ordering is on canonical values, so PRIME + 1 reduces to 1
+    assert_eq!(FieldElement::new(PRIME + 1).cmp(&FieldElement::ONE), std::cmp::Ordering::Equal);
+
+    let values = [5u64, PRIME - 1, 0, 17, 1 << 40, 3, PRIME - 1, 0xFFFFFFFF];
+    let mut elements: Vec<FieldElement> = values.iter().map(|&v| FieldElement::new(v)).collect();
+    elements.sort();
+
+    let mut sorted_values = values;
+    sorted_values.sort();
+    let sorted: Vec<u64> = elements.iter().map(|e| e.value()).collect();
+    assert_eq!(sorted, sorted_values);
+
+    assert_eq!(elements.iter().max(), Some(&FieldElement::new(PRIME - 1)));
+    assert_eq!(elements.iter().min(), Some(&FieldElement::ZERO));
+}
+
+#[test]
+fn test_hash() {
+    use std::collections::{HashMap, HashSet};
+
+    // the same element created through different constructors
+    let set: HashSet<FieldElement> = [
+        FieldElement::new(42),
+        FieldElement::new(PRIME + 42),
+        FieldElement::from(42u8),
+        FieldElement::from(42u32),
+        FieldElement::try_from(42u64.to_le_bytes()).unwrap(),
+        FieldElement::new(40) + FieldElement::new(2),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.len(), 1);
+
+    let mut map = HashMap::new();
+    map.insert(FieldElement::ONE, "one");
+    assert_eq!(map.get(&FieldElement::new(PRIME + 1)), Some(&"one"));
+}
+
+#[test]
+fn test_default() {
+    assert_eq!(FieldElement::default(), FieldElement::ZERO);
+    assert_eq!(<[FieldElement; 4]>::default(), [FieldElement::ZERO; 4]);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_arbitrary() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let mut rng = test_rng(4);
+    let mut bytes: Vec<u8> = (0..4096).flat_map(|_| rng.gen::<u64>().to_le_bytes()).collect();
+    // values at and above PRIME must be reduced as well
+    bytes.extend_from_slice(&[0xFF; 64]);
+
+    let mut u = Unstructured::new(&bytes);
+    while !u.is_empty() {
+        let x = FieldElement::arbitrary(&mut u).unwrap();
+        assert!(x.value() < PRIME);
+    }
+
+    // the all-ones input reduces to 2^128 - 1 mod PRIME
+    let mut u = Unstructured::new(&[0xFF; 16]);
+    let x = FieldElement::arbitrary(&mut u).unwrap();
+    assert_eq!(x, FieldElement::from_wide(u128::MAX));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    for value in [0, 1, 0x0123456789ABCDEF, PRIME - 1] {
+        let r = FieldElement::new(value);
+        let json = serde_json::to_string(&r).unwrap();
+        assert_eq!(json, value.to_string());
+        assert_eq!(serde_json::from_str::<FieldElement>(&json).unwrap(), r);
+    }
+
+    let elements = [1u64, 2, 3].map(FieldElement::new);
+    let json = serde_json::to_string(&elements).unwrap();
+    assert_eq!(json, "[1,2,3]");
+    assert_eq!(serde_json::from_str::<[FieldElement; 3]>(&json).unwrap(), elements);
+
+    // non-canonical values are rejected rather than reduced
+    let error = serde_json::from_str::<FieldElement>(&PRIME.to_string()).unwrap_err();
+    assert!(error.to_string().contains(&FieldError::NonCanonical.to_string()));
+    assert!(serde_json::from_str::<FieldElement>(&u64::MAX.to_string()).is_err());
+    assert!(serde_json::from_str::<FieldElement>("-1").is_err());
+}
+
+#[test]
+fn test_exp_ct() {
+    assert_eq!(FieldElement::ZERO.exp_ct(0), FieldElement::ONE);
+    assert_eq!(FieldElement::ZERO.exp_ct(5), FieldElement::ZERO);
+    assert_eq!(FieldElement::new(3).exp_ct(0), FieldElement::ONE);
+
+    let mut rng = test_rng(5);
+    for base in test_elements(32, 5) {
+        let pow = rng.gen::<u64>();
+        assert_eq!(base.exp_ct(pow), base.exp_u64(pow));
+    }
+
+    let base = FieldElement::new(69);
+    for pow in [1, 2, 7, PRIME - 1, PRIME, u64::MAX] {
+        assert_eq!(base.exp_ct(pow), base.exp_u64(pow));
+    }
+}
+
+#[test]
+fn test_try_inv() {
+    assert_eq!(FieldElement::ZERO.try_inv(), None);
+    assert_eq!(FieldElement::ONE.try_inv(), Some(FieldElement::ONE));
+
+    for x in test_elements(16, 6) {
+        assert_eq!(x.try_inv().unwrap() * x, FieldElement::ONE);
+    }
+}
+
+#[test]
+fn test_mul_acc() {
+    let max = FieldElement::new(PRIME - 1);
+    assert_eq!(mul_acc(max, max, max), max + max * max);
+
+    for values in test_elements(48, 7).chunks_exact(3) {
+        let (acc, a, b) = (values[0], values[1], values[2]);
+        assert_eq!(mul_acc(acc, a, b), acc + a * b);
+    }
+}
+
+#[test]
+fn test_inner_product() {
+    let naive = |a: &[FieldElement], b: &[FieldElement]| {
+        a.iter().zip(b.iter()).fold(FieldElement::ZERO, |acc, (&x, &y)| acc + x * y)
+    };
+
+    assert_eq!(inner_product(&[], &[]), FieldElement::ZERO);
+
+    // the largest products, which fill up both accumulators the fastest
+    let max = vec![FieldElement::new(PRIME - 1); 1000];
+    assert_eq!(inner_product(&max, &max), naive(&max, &max));
+
+    for len in [1, 2, 12, 100] {
+        let a = test_elements(len, 8);
+        let b = test_elements(len, 9);
+        assert_eq!(inner_product(&a, &b), naive(&a, &b));
+    }
+}
+
+#[test]
+fn test_unreduced_reduce_full() {
+    // the eager reduction of low + 2^64 * high
+    let eager = |low: u128, high: u128| {
+        FieldElement::from_wide(low)
+            + FieldElement::from_wide(high) * FieldElement::new((1 << 32) - 1)
+    };
+
+    let bounds = [0, 1, PRIME as u128, u64::MAX as u128, (1 << 127) - 1];
+    for low in bounds {
+        for high in bounds.into_iter().chain([u128::MAX]) {
+            let acc = FieldElementUnreduced { low, high };
+            assert_eq!(acc.reduce_full(), eager(low, high));
+        }
+    }
+
+    let mut acc = FieldElementUnreduced::ZERO;
+    assert_eq!(acc.reduce_full(), FieldElement::ZERO);
+    acc.add_product(FieldElement::new(PRIME - 1), FieldElement::new(PRIME - 1));
+    assert_eq!(acc.reduce_full(), FieldElement::ONE);
+}
+
+#[test]
+#[should_panic]
+fn test_inner_product_length_mismatch() {
+    inner_product(&[FieldElement::ONE], &[]);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut element = FieldElement::new(PRIME - 1);
+    element.zeroize();
+    assert_eq!(element.value(), 0);
+
+    let mut elements = [FieldElement::new(3), FieldElement::new(5)];
+    elements.zeroize();
+    assert_eq!(elements, [FieldElement::ZERO; 2]);
+}
+
+#[cfg(feature = "subtle")]
+#[test]
+fn test_constant_time() {
+    use subtle::Choice;
+
+    let elements = test_elements(4, 10).repeat(2);
+    for &a in elements.iter() {
+        for &b in elements.iter() {
+            assert_eq!(bool::from(a.ct_eq(b)), a == b);
+            assert_eq!(FieldElement::conditional_select(&a, &b, Choice::from(0)), a);
+            assert_eq!(FieldElement::conditional_select(&a, &b, Choice::from(1)), b);
+        }
+    }
+
+    // equality is on canonical values
+    assert!(bool::from(FieldElement::new(PRIME + 1).ct_eq(FieldElement::ONE)));
+}
+
+#[test]
+fn test_pow_limbs() {
+    for x in test_elements(16, 11) {
+        for k in [0, 1, 2, 7, 0xFFFF, PRIME - 2, u64::MAX] {
+            assert_eq!(x.pow_limbs(&[k, 0]), x.exp_u64(k));
+            assert_eq!(x.pow_limbs(&[k]), x.exp_u64(k));
+        }
+        assert_eq!(x.pow_limbs(&[]), FieldElement::ONE);
+
+        // x^(a + b * 2^64) = x^a * (x^(2^64))^b
+        let x_2_64 = (0..64).fold(x, |acc, _| acc.square());
+        let (a, b) = (0x0123456789ABCDEF, 0xFEDCBA9876543210);
+        assert_eq!(x.pow_limbs(&[a, b]), x.exp_u64(a) * x_2_64.exp_u64(b));
+
+        // x^(p^2) = x, since x^p = x
+        let p_squared = (PRIME as u128) * (PRIME as u128);
+        let limbs = [p_squared as u64, (p_squared >> 64) as u64, 0];
+        assert_eq!(x.pow_limbs(&limbs), x);
+    }
+}
+
+#[test]
+fn test_legendre() {
+    assert_eq!(FieldElement::ZERO.legendre(), 0);
+    assert_eq!(FieldElement::ONE.legendre(), 1);
+    assert_eq!(FieldElement::new(4).legendre(), 1);
+    assert_eq!(FieldElement::QUADRATIC_NON_RESIDUE.legendre(), -1);
+    assert_eq!(FieldElement::new(PRIME - 1).legendre(), 1);
+
+    assert!(FieldElement::ZERO.is_quadratic_residue());
+    assert!(FieldElement::ONE.is_quadratic_residue());
+    assert!(!FieldElement::QUADRATIC_NON_RESIDUE.is_quadratic_residue());
+
+    // consistent with sqrt, and multiplicative
+    for x in test_elements(64, 12).into_iter().chain([FieldElement::ZERO]) {
+        assert_eq!(x.is_quadratic_residue(), x.sqrt().is_some());
+        assert_eq!(x.square().legendre(), if x == FieldElement::ZERO { 0 } else { 1 });
+        let y = x * FieldElement::QUADRATIC_NON_RESIDUE;
+        assert_eq!(y.legendre(), -x.legendre());
+    }
+}
+
+#[test]
+fn test_from_wide() {
+    assert_eq!(FieldElement::from_wide(0), FieldElement::ZERO);
+    assert_eq!(FieldElement::from_wide(PRIME as u128), FieldElement::ZERO);
+    assert_eq!(FieldElement::from_wide(u64::MAX as u128), FieldElement::new(u64::MAX));
+    assert_eq!(FieldElement::from_wide(u128::MAX).value(), (u128::MAX % PRIME as u128) as u64);
+
+    let mut rng = test_rng(13);
+    for _ in 0..64 {
+        let (a, b) = (rng.gen::<u64>(), rng.gen::<u64>());
+        let product = FieldElement::from_wide(a as u128 * b as u128);
+        assert_eq!(product, FieldElement::from(a) * FieldElement::from(b));
+        assert!(product.value() < PRIME);
+
+        let wide = (a as u128) << 64 | b as u128;
+        assert_eq!(FieldElement::from_wide(wide).value(), (wide % PRIME as u128) as u64);
+    }
+}
+
+#[test]
+fn test_from_u128() {
+    assert_eq!(FieldElement::from_u128(PRIME as u128 + 5), FieldElement::from(5u64));
+    assert_eq!(FieldElement::from(PRIME as u128 + 5), FieldElement::from(5u64));
+    assert_eq!(FieldElement::from_u128(u128::MAX), FieldElement::from_wide(u128::MAX));
+    assert_eq!(FieldElement::from(u64::MAX as u128), FieldElement::from(u64::MAX));
+}
+
+#[test]
+fn test_checked_div() {
+    for (x, y) in test_elements(16, 14).into_iter().zip(test_elements(16, 15)) {
+        assert_eq!(x.checked_div(y), Ok(x * y.inv()));
+        assert_eq!(x.checked_div(y), Ok(x / y));
+        assert_eq!(x.checked_div(FieldElement::ZERO), Err(FieldError::DivisionByZero));
+    }
+    assert_eq!(
+        FieldElement::ZERO.checked_div(FieldElement::ZERO),
+        Err(FieldError::DivisionByZero)
+    );
+    assert_eq!(FieldElement::ZERO.checked_div(FieldElement::ONE), Ok(FieldElement::ZERO));
+}
+
+#[test]
+fn test_batch_arithmetic() {
+    // edge values first, then pseudo-random ones
+    let edges = [0, 1, 2, 0xFFFFFFFF, 0x100000000, PRIME - 2, PRIME - 1, 1 << 63];
+    let values = |seed: u64| -> Vec<FieldElement> {
+        let edges = edges.iter().copied().map(FieldElement::new);
+        edges.chain(test_elements(24, seed)).collect()
+    };
+    let a = values(16);
+    let mut b = values(17);
+    b[..edges.len()].reverse();
+
+    // lengths which are not a multiple of the number of SIMD lanes leave a
+    // remainder for the scalar loop
+    for len in 0..=a.len() {
+        let mut out = vec![FieldElement::ZERO; len];
+        add_batch(&a[..len], &b[..len], &mut out);
+        for i in 0..len {
+            assert_eq!(out[i], a[i] + b[i]);
+        }
+
+        mul_batch(&a[..len], &b[..len], &mut out);
+        for i in 0..len {
+            assert_eq!(out[i], a[i] * b[i]);
+        }
+    }
+
+    // every pair of edge values
+    let b: Vec<_> = edges.iter().map(|&y| FieldElement::new(y)).collect();
+    let mut out = vec![FieldElement::ZERO; edges.len()];
+    for &x in edges.iter() {
+        let a = vec![FieldElement::new(x); edges.len()];
+        add_batch(&a, &b, &mut out);
+        assert!(out.iter().zip(b.iter()).all(|(&z, &y)| z == a[0] + y));
+        mul_batch(&a, &b, &mut out);
+        assert!(out.iter().zip(b.iter()).all(|(&z, &y)| z == a[0] * y));
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_batch_length_mismatch() {
+    let mut out = [FieldElement::ZERO; 2];
+    add_batch(&[FieldElement::ONE; 3], &[FieldElement::ONE; 3], &mut out);
+}
+
+#[test]
+fn test_from_bytes_wide() {
+    let mut inputs = vec![[0u8; 16], [0xFF; 16]];
+    inputs.push((PRIME as u128).to_le_bytes());
+    inputs.push(((PRIME as u128) << 64 | (PRIME - 1) as u128).to_le_bytes());
+    let mut rng = test_rng(18);
+    for _ in 0..32 {
+        inputs.push(rng.gen::<u128>().to_le_bytes());
+    }
+
+    for bytes in inputs {
+        let element = FieldElement::from_bytes_wide(&bytes);
+        assert_eq!(element, FieldElement::new(reduce(u128::from_le_bytes(bytes))));
+        assert_eq!(element.value() as u128, u128::from_le_bytes(bytes) % PRIME as u128);
+    }
+
+    // the low 8 bytes alone give the same element as from_bytes
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&12345u64.to_le_bytes());
+    assert_eq!(FieldElement::from_bytes_wide(&bytes), FieldElement::new(12345));
+}
+
+#[test]
+fn test_mul_small() {
+    let mut values = vec![0, 1, 2, 0xFFFFFFFF, PRIME - 2, PRIME - 1];
+    values.extend(test_elements(32, 19).iter().map(FieldElement::value));
+    let mut rng = test_rng(20);
+
+    for &value in values.iter() {
+        let a = FieldElement::new(value);
+        for n in [0, 1, 2, 3, 4, 255, 0xFFFFFFFF, 1 << 32, PRIME - 1, u64::MAX] {
+            assert_eq!(a.mul_small(n), a * FieldElement::from(n));
+        }
+        assert_eq!(a.double(), a + a);
+        assert!(a.mul_small(0xFFFFFFFF).value() < PRIME);
+
+        // n below 2^32 takes the folding path
+        for _ in 0..16 {
+            let n = rng.gen::<u32>() as u64;
+            assert_eq!(a.mul_small(n), a * FieldElement::from(n));
+        }
+    }
+}
+
+#[test]
+fn test_reduce_high_bits() {
+    // bits 96..128 all set, with every combination of extreme lower parts
+    let high = (u32::MAX as u128) << 96;
+    let middles = [0, 1, u32::MAX as u128];
+    let lows = [0, 1, 0xFFFFFFFF, PRIME as u128 - 1, PRIME as u128, u64::MAX as u128];
+    for &middle in middles.iter() {
+        for &low in lows.iter() {
+            let x = high | middle << 64 | low;
+            let reduced = reduce(x);
+            assert_eq!(reduced as u128 % PRIME as u128, x % PRIME as u128);
+            assert_eq!(FieldElement::from_wide(x).value() as u128, x % PRIME as u128);
+        }
+    }
+
+    // 2^96 = -1 (mod p), so the largest high part alone is -(2^32 - 1)
+    let x = high;
+    assert_eq!(FieldElement::from_wide(x), -FieldElement::new(u32::MAX as u64));
+    assert_eq!(FieldElement::from_wide(u128::MAX).value() as u128, u128::MAX % PRIME as u128);
+}
+
+#[test]
+fn test_bytes_to_field_wide() {
+    // 9 bytes, as reduced into round constants: 0x090807060504030201 mod p
+    let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    assert_eq!(bytes_to_field_wide(&bytes), FieldElement::new(578437734407012856));
+    assert_eq!(bytes_to_field_wide(&[0xFF; 9]), FieldElement::new(1099511627519));
+
+    // the byte order is the one of to_bytes
+    for element in test_elements(32, 21) {
+        assert_eq!(bytes_to_field_wide(&element.to_bytes()), element);
+    }
+    assert_eq!(bytes_to_field_wide(&[]), FieldElement::ZERO);
+    assert_eq!(bytes_to_field_wide(&[1, 2]), FieldElement::new(0x0201));
+    assert_eq!(bytes_to_field_wide(&[0xFF; 16]), FieldElement::from_wide(u128::MAX));
+}
+
+#[test]
+#[should_panic]
+fn test_bytes_to_field_wide_too_long() {
+    bytes_to_field_wide(&[0; 17]);
+}
+
+#[test]
+fn test_write_bytes() {
+    let elements = [
+        FieldElement::new(1),
+        FieldElement::new(PRIME - 1),
+        FieldElement::new(0x0123456789),
+    ];
+    let expected = elements_to_bytes(&elements);
+
+    // exact size
+    let mut out = [0u8; 24];
+    assert_eq!(write_bytes(&elements, &mut out), Ok(24));
+    assert_eq!(out[..], expected[..]);
+
+    // oversized, the tail is left as is
+    let mut out = [0xAAu8; 30];
+    assert_eq!(write_bytes(&elements, &mut out), Ok(24));
+    assert_eq!(out[..24], expected[..]);
+    assert_eq!(out[24..], [0xAA; 6]);
+
+    // too small, nothing is written
+    let mut out = [0u8; 23];
+    assert_eq!(write_bytes(&elements, &mut out), Err(FieldError::InvalidLength));
+    assert_eq!(out, [0u8; 23]);
+
+    assert_eq!(write_bytes(&[], &mut []), Ok(0));
+}
+
+#[test]
+fn test_pow7() {
+    assert_eq!(FieldElement::ZERO.pow7(), FieldElement::ZERO);
+    assert_eq!(FieldElement::ONE.pow7(), FieldElement::ONE);
+    assert_eq!(FieldElement::new(PRIME - 1).pow7(), FieldElement::new(PRIME - 1));
+
+    for x in test_elements(100, 22) {
+        assert_eq!(x.pow7(), x.exp_u64(7));
+    }
+}
+
+#[test]
+fn test_u64_operators() {
+    for x in test_elements(20, 23) {
+        assert_eq!(x * 3u64, x * FieldElement::from(3u64));
+        assert_eq!(3u64 * x, x * FieldElement::from(3u64));
+        assert_eq!(x + 5u64, x + FieldElement::from(5u64));
+        assert_eq!(5u64 + x, x + FieldElement::from(5u64));
+        assert_eq!(x - 7u64, x - FieldElement::from(7u64));
+        assert_eq!(7u64 - x, FieldElement::from(7u64) - x);
+    }
+
+    // values of at least PRIME are reduced
+    let x = FieldElement::new(42);
+    assert_eq!(x + PRIME, x);
+    assert_eq!(x * (PRIME + 2), x.double());
+    assert_eq!(x - u64::MAX, x - FieldElement::new(u64::MAX - PRIME));
+}
+
+#[test]
+fn test_const_arithmetic() {
+    const TWO: FieldElement = FieldElement::ONE.const_add(FieldElement::ONE);
+    const MINUS_FOUR: FieldElement = FieldElement::new(PRIME - 2).const_mul(TWO);
+    assert_eq!(TWO, FieldElement::new(2));
+    assert_eq!(MINUS_FOUR, -FieldElement::new(4));
+
+    let values = [0, 1, 2, 1 << 32, (1 << 32) - 1, PRIME - 2, PRIME - 1, 0x0123456789ABCDEF];
+    for &x in values.iter() {
+        for &y in values.iter() {
+            let (x, y) = (FieldElement::new(x), FieldElement::new(y));
+            assert_eq!(x.const_add(y), x + y);
+            assert_eq!(x.const_mul(y), x * y);
+        }
+    }
+}
+
+#[test]
+fn test_from_raw() {
+    for value in [0, 1, 42, 1 << 32, PRIME - 1] {
+        // SAFETY: all the values are less than PRIME.
+        let element = unsafe { FieldElement::from_raw(value) };
+        assert_eq!(element, FieldElement::new(value));
+        assert_eq!(element.as_canonical_u64(), value);
+    }
+
+    assert_eq!(FieldElement::new(PRIME + 3).as_canonical_u64(), 3);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "the value must be less than PRIME")]
+fn test_from_raw_not_canonical() {
+    // SAFETY: this breaks the contract on purpose, which debug builds catch.
+    let _ = unsafe { FieldElement::from_raw(PRIME) };
+}
+
+#[test]
+fn test_montgomery() {
+    // R = 2^64 = 2^32 - 1 (mod p)
+    assert_eq!(FieldElement::ONE.to_montgomery(), (1 << 32) - 1);
+    assert_eq!(FieldElement::ZERO.to_montgomery(), 0);
+    assert_eq!(FieldElement::from_montgomery((1 << 32) - 1), FieldElement::ONE);
+    assert_eq!(
+        FieldElement::from_montgomery(u64::MAX),
+        FieldElement::from_montgomery(u64::MAX - PRIME)
+    );
+
+    let mut values = test_elements(100, 24);
+    values.extend([FieldElement::ZERO, FieldElement::new(PRIME - 1)]);
+    for &x in values.iter() {
+        let x_mont = x.to_montgomery();
+        assert!(x_mont < PRIME);
+        assert_eq!(FieldElement::from_montgomery(x_mont), x);
+
+        for &y in values.iter().take(10) {
+            let product = FieldElement::mul_montgomery(x_mont, y.to_montgomery());
+            assert!(product < PRIME);
+            assert_eq!(product, (x * y).to_montgomery());
+            assert_eq!(FieldElement::from_montgomery(product), x * y);
+        }
+    }
+}
+
+#[test]
+fn test_assign_reference_operators() {
+    let row = test_elements(20, 25);
+
+    let mut sum = FieldElement::ZERO;
+    let mut difference = FieldElement::ZERO;
+    let mut product = FieldElement::ONE;
+    for x in &row {
+        sum += x;
+        difference -= x;
+        product *= x;
+    }
+    assert_eq!(sum, row.iter().sum());
+    assert_eq!(difference, -sum);
+    assert_eq!(product, row.iter().product());
+}
+
+#[test]
+fn test_hex() {
+    assert_eq!(format!("{:x}", FieldElement::from(255u64)), "00000000000000ff");
+    assert_eq!(format!("{:X}", FieldElement::from(255u64)), "00000000000000FF");
+    assert_eq!(format!("{:#x}", FieldElement::from(255u64)), "0x00000000000000ff");
+    assert_eq!(format!("{:x}", FieldElement::new(PRIME - 1)), "ffffffff00000000");
+    assert_eq!(format!("{:X}", FieldElement::new(0x0123456789ABCDEF)), "0123456789ABCDEF");
+
+    // the digits are the ones of the big-endian serialization
+    let x = FieldElement::new(0xFEDCBA9876543210);
+    let expected: String = x.to_bytes_be().iter().map(|byte| format!("{:02x}", byte)).collect();
+    assert_eq!(format!("{:x}", x), expected);
+}
+
+#[test]
+fn test_pow7_state() {
+    let mut state = test_array::<12>(26);
+    state[0] = FieldElement::ZERO;
+    state[1] = FieldElement::new(PRIME - 1);
+
+    let expected = state.map(FieldElement::pow7);
+    pow7_state(&mut state);
+    assert_eq!(state, expected);
+
+    // widths that are not a multiple of the SIMD lanes
+    let mut state = [FieldElement::new(3); 5];
+    pow7_state(&mut state);
+    assert_eq!(state, [FieldElement::new(2187); 5]);
 }
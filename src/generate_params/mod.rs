@@ -0,0 +1,468 @@
+use alloc::{format, string::String, vec, vec::Vec};
+
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
+
+use super::{
+    field_element::{bytes_to_field_wide, FieldElement, ALPHA, ALPHA_INV, PRIME},
+    hash::{hash, Digest, DIGEST_SIZE},
+    rescue::is_mds,
+    utils::errors::ParamError,
+};
+
+#[cfg(test)]
+mod tests;
+
+// MDS MATRIX
+// =============================================================================
+
+/// The largest state width supported by `generate_mds`. The generated matrices
+/// of every width up to this one are checked to be MDS with `is_mds`, which
+/// becomes too expensive beyond it: up to width 8 by `generate_mds` itself in
+/// debug builds, and from width 9 by the ignored `test_generate_mds_is_mds`.
+pub const MAX_MDS_WIDTH: usize = 12;
+
+/// Generate the WIDTH x WIDTH MDS matrix used by the linear layer of the
+/// Rescue-XLIX permutation, following the reference Rescue-Prime implementation.
+///
+/// The matrix is derived from the WIDTH x 2*WIDTH Vandermonde matrix
+/// V[i][j] = g^(i*j), where g is the GENERATOR of the field. V is the generator
+/// matrix of a Reed-Solomon code, which is MDS. Bringing V to its reduced row
+/// echelon form [I | A] gives a systematic generator matrix of the same code,
+/// and the MDS matrix is the transpose of its right half A.
+///
+/// The code is MDS whenever its 2 * WIDTH evaluation points g^0, g^1, ... are
+/// distinct, but the MDS property of the result is only checked for widths up
+/// to MAX_MDS_WIDTH: with a debug assertion up to width 8, and for widths 9 to
+/// 12 only by `test_generate_mds_is_mds`, run with `--ignored`. Returns an
+/// UnsupportedWidth error if WIDTH is zero or greater than MAX_MDS_WIDTH,
+/// rather than a matrix that has not been checked.
+pub fn generate_mds<const WIDTH: usize>() -> Result<[[FieldElement; WIDTH]; WIDTH], ParamError> {
+    if WIDTH == 0 || WIDTH > MAX_MDS_WIDTH {
+        return Err(ParamError::UnsupportedWidth);
+    }
+
+    let g = FieldElement::GENERATOR;
+
+    // Build the Vandermonde matrix, row by row: row i is (g^i)^0, (g^i)^1, ...
+    let mut v = vec![vec![FieldElement::ZERO; 2 * WIDTH]; WIDTH];
+    let mut g_i = FieldElement::ONE;
+    for row in v.iter_mut() {
+        let mut entry = FieldElement::ONE;
+        for element in row.iter_mut() {
+            *element = entry;
+            entry *= g_i;
+        }
+        g_i *= g;
+    }
+
+    // Bring V to its reduced row echelon form using Gauss-Jordan elimination.
+    // The left half of V is an invertible Vandermonde matrix, since the powers
+    // of g are distinct, so a non-zero pivot always exists in the i-th column.
+    for col in 0..WIDTH {
+        let pivot = (col..WIDTH)
+            .find(|&row| v[row][col] != FieldElement::ZERO)
+            .expect("the Vandermonde matrix must be invertible");
+        v.swap(col, pivot);
+
+        let pivot_inv = v[col][col].inv();
+        for element in v[col].iter_mut() {
+            *element *= pivot_inv;
+        }
+
+        let pivot_row = v[col].clone();
+        for (i, row) in v.iter_mut().enumerate() {
+            let factor = row[col];
+            if i == col || factor == FieldElement::ZERO {
+                continue;
+            }
+            for (element, &pivot_element) in row.iter_mut().zip(pivot_row.iter()) {
+                *element -= factor * pivot_element;
+            }
+        }
+    }
+
+    // The MDS matrix is the transpose of the right half of the echelon form.
+    let mut mds = [[FieldElement::ZERO; WIDTH]; WIDTH];
+    for (i, mds_row) in mds.iter_mut().enumerate() {
+        for (j, entry) in mds_row.iter_mut().enumerate() {
+            *entry = v[j][WIDTH + i];
+        }
+    }
+
+    // Checking every submatrix quickly becomes expensive, so only small widths
+    // are checked here; the others are covered by `test_generate_mds_is_mds`.
+    debug_assert!(WIDTH > 8 || is_mds(&mds), "the generated matrix must be MDS");
+    validate_mds(&mds)?;
+    Ok(mds)
+}
+
+/// Check that no entry of a candidate MDS matrix is zero.
+///
+/// Every entry is a 1 x 1 submatrix, so an MDS matrix never has zero entries.
+/// This is much cheaper than `is_mds`, which checks every square submatrix, and
+/// catches matrices that are obviously broken.
+pub fn validate_mds<const WIDTH: usize>(
+    mds: &[[FieldElement; WIDTH]; WIDTH],
+) -> Result<(), ParamError> {
+    if mds.iter().flatten().any(|&entry| entry == FieldElement::ZERO) {
+        return Err(ParamError::ZeroMdsEntry);
+    }
+    Ok(())
+}
+
+/// Return a fingerprint of the MDS matrix generated by `generate_mds`, the
+/// `hash` of its entries flattened row by row.
+///
+/// Comparing it to a committed value catches an unintended change to the
+/// construction of the matrix, which would silently change every digest.
+pub fn mds_fingerprint<const WIDTH: usize>() -> Result<Digest<DIGEST_SIZE>, ParamError> {
+    let mds = generate_mds::<WIDTH>()?;
+    let entries: Vec<FieldElement> = mds.iter().flatten().copied().collect();
+    Ok(hash(&entries))
+}
+
+// S-BOX EXPONENTS
+// =============================================================================
+
+/// Return the smallest odd integer alpha >= 3 that is coprime to PRIME - 1, so
+/// that x -> x^alpha is a permutation of the field.
+pub fn smallest_alpha() -> u64 {
+    (3..)
+        .step_by(2)
+        .find(|&alpha| gcd(alpha, PRIME - 1) == 1)
+        .expect("an odd prime not dividing PRIME - 1 always exists")
+}
+
+/// Return the inverse of alpha modulo PRIME - 1, computed with the extended
+/// Euclidean algorithm, so that x -> x^alpha_inverse(alpha) undoes x -> x^alpha.
+///
+/// # Panics
+/// Panics if alpha is not coprime to PRIME - 1, since it has no inverse then.
+pub fn alpha_inverse(alpha: u64) -> u64 {
+    let modulus = (PRIME - 1) as i128;
+
+    // Invariant: old_s * alpha = old_r (mod modulus) and s * alpha = r (mod modulus).
+    let (mut old_r, mut r) = (alpha as i128, modulus);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+
+    assert!(old_r == 1, "alpha must be coprime to PRIME - 1 to be invertible.");
+    old_s.rem_euclid(modulus) as u64
+}
+
+// NUMBER OF ROUNDS
+// =============================================================================
+
+/// The largest security level in bits supported by `num_rounds`, the largest
+/// one for which 2^security_level fits in an f64.
+pub const MAX_SECURITY_LEVEL: usize = 1023;
+
+/// Return the number of rounds N of the Rescue-XLIX permutation needed to reach
+/// the given security level in bits, following the reference Rescue-Prime
+/// implementation.
+///
+/// The smallest number of rounds l1 that resists Groebner basis attacks is the
+/// smallest one for which binomial(v + d, v)^2 > 2^security_level, where
+/// v = width * (l1 - 1) + rate is the number of variables and
+/// d = floor((alpha - 1) * width * (l1 - 1) / 2 + 2) is the degree of
+/// regularity of the system of equations. The result is then increased by a
+/// 50% security margin, with a minimum of 5 rounds before the margin is added.
+///
+/// # Panics
+/// Panics if the capacity is greater than the width, if alpha is less than 2, or
+/// if the security level is greater than MAX_SECURITY_LEVEL.
+pub fn num_rounds(security_level: usize, width: usize, capacity: usize, alpha: u64) -> usize {
+    assert!(capacity <= width, "The capacity must not be greater than the width.");
+    assert!(alpha >= 2, "alpha must be at least 2 for the S-box to have a degree.");
+    assert!(security_level <= MAX_SECURITY_LEVEL, "2^security_level must fit in an f64.");
+    let rate = width - capacity;
+
+    let mut target = 1.0f64;
+    for _ in 0..security_level {
+        target *= 2.0;
+    }
+
+    // The reference implementation searches for l1 in 1..25 and settles on 24
+    // if no smaller number of rounds is enough.
+    let l1 = (1..25)
+        .find(|&l1| {
+            let v = width * (l1 - 1) + rate;
+            let dcon = (alpha as usize - 1) * width * (l1 - 1) / 2 + 2;
+            let complexity = binomial(v + dcon, v);
+            complexity * complexity > target
+        })
+        .unwrap_or(24);
+
+    // ceil(1.5 * max(5, l1))
+    (3 * l1.max(5)).div_ceil(2)
+}
+
+// ROUND CONSTANTS
+// =============================================================================
+
+/// The number of pseudorandom bytes reduced into each round constant, which is
+/// ceil(|p| / 8) + 1 where |p| is the bit length of PRIME, as in the reference
+/// implementation. The extra byte makes the bias of the reduction negligible.
+const BYTES_PER_FIELD: usize = (u64::BITS - PRIME.leading_zeros()).div_ceil(8) as usize + 1;
+
+// The chunks of bytes are folded into a u128 before being reduced.
+const _: () = assert!(BYTES_PER_FIELD <= 16);
+
+/// Generate the round constants of the Rescue-XLIX permutation with state width
+/// WIDTH, RATE rate elements and N rounds, for the given security level in bits,
+/// following the reference Rescue-Prime implementation.
+///
+/// The seed string "Rescue-XLIX(p,w,c,sec)" is expanded with SHAKE256 into
+/// 2 * WIDTH * N chunks of bytes. Each chunk is interpreted as an integer with
+/// the least significant byte first and reduced modulo PRIME. The constants of
+/// round i are chunks 2 * WIDTH * i..2 * WIDTH * (i + 1), the first half of
+/// which goes into `round_constants[0][i]` and the second half into
+/// `round_constants[1][i]`.
+///
+/// Returns an error if the rate exceeds the state width, if the state width is
+/// zero or if the security level is zero, and an InvalidRoundConstant error if
+/// the pseudorandom bytes cannot be reduced into the constants.
+pub fn compute_round_constants<const WIDTH: usize, const RATE: usize, const N: usize>(
+    security_level: usize,
+) -> Result<[[[FieldElement; WIDTH]; N]; 2], ParamError> {
+    validate_params(WIDTH, RATE, security_level)?;
+    let [first, second] = generate_round_constants::<WIDTH>(WIDTH - RATE, security_level, N)?;
+
+    let mut round_constants = [[[FieldElement::ZERO; WIDTH]; N]; 2];
+    round_constants[0].copy_from_slice(&first);
+    round_constants[1].copy_from_slice(&second);
+    Ok(round_constants)
+}
+
+/// Generate the round constants of the Rescue-XLIX permutation as described in
+/// `compute_round_constants`, for a number of rounds only known at runtime.
+pub(crate) fn generate_round_constants<const WIDTH: usize>(
+    capacity: usize,
+    security_level: usize,
+    num_rounds: usize,
+) -> Result<[Vec<[FieldElement; WIDTH]>; 2], ParamError> {
+    let byte_string = round_constant_bytes(WIDTH, capacity, security_level, num_rounds);
+
+    let mut round_constants = [
+        vec![[FieldElement::ZERO; WIDTH]; num_rounds],
+        vec![[FieldElement::ZERO; WIDTH]; num_rounds],
+    ];
+
+    // The chunks are independent of each other, so they can be reduced in
+    // parallel. Both paths produce the same constants.
+    #[cfg(feature = "rayon")]
+    fill_round_constants_par(&byte_string, &mut round_constants)?;
+    #[cfg(not(feature = "rayon"))]
+    fill_round_constants(&byte_string, &mut round_constants)?;
+
+    Ok(round_constants)
+}
+
+// CODE GENERATION
+// =============================================================================
+
+/// Return Rust source declaring the parameters of the instance with state width
+/// WIDTH and RATE rate elements for the given security level, so that they can
+/// be frozen into a downstream crate instead of being generated at runtime.
+///
+/// The snippet declares `WIDTH`, `RATE`, `NUM_ROUNDS`, `ALPHA` and `ALPHA_INV`,
+/// together with `MDS: [[u64; WIDTH]; WIDTH]` from `generate_mds` and
+/// `ROUND_CONSTANTS: [[[u64; WIDTH]; NUM_ROUNDS]; 2]` laid out as in
+/// `compute_round_constants`, with the number of rounds given by `num_rounds`.
+/// The entries are the canonical values of the field elements, which can be
+/// turned back into elements with the const fn `FieldElement::new`.
+///
+/// Returns an error for the same parameters as `compute_round_constants`.
+pub fn emit_params_rust<const WIDTH: usize, const RATE: usize>(
+    security_level: usize,
+) -> Result<String, ParamError> {
+    validate_params(WIDTH, RATE, security_level)?;
+    let capacity = WIDTH - RATE;
+    let num_rounds = num_rounds(security_level, WIDTH, capacity, ALPHA);
+    let round_constants = generate_round_constants::<WIDTH>(capacity, security_level, num_rounds)?;
+    let mds = generate_mds::<WIDTH>()?;
+
+    let mut out = format!(
+        "// Rescue-Prime parameters over the field of {PRIME} elements, with a state of\n\
+         // {WIDTH} elements, {RATE} of which make up the rate, for {security_level} bits of security.\n\n"
+    );
+    out.push_str(&format!("pub const WIDTH: usize = {WIDTH};\n"));
+    out.push_str(&format!("pub const RATE: usize = {RATE};\n"));
+    out.push_str(&format!("pub const NUM_ROUNDS: usize = {num_rounds};\n"));
+    out.push_str(&format!("pub const ALPHA: u64 = {ALPHA};\n"));
+    out.push_str(&format!("pub const ALPHA_INV: u64 = {ALPHA_INV};\n\n"));
+
+    out.push_str("pub const MDS: [[u64; WIDTH]; WIDTH] = [\n");
+    for row in mds.iter() {
+        out.push_str(&format!("    {},\n", emit_row(row)));
+    }
+    out.push_str("];\n\n");
+
+    out.push_str("pub const ROUND_CONSTANTS: [[[u64; WIDTH]; NUM_ROUNDS]; 2] = [\n");
+    for half in round_constants.iter() {
+        out.push_str("    [\n");
+        for row in half.iter() {
+            out.push_str(&format!("        {},\n", emit_row(row)));
+        }
+        out.push_str("    ],\n");
+    }
+    out.push_str("];\n");
+
+    Ok(out)
+}
+
+// HELPER FUNCTIONS
+// =============================================================================
+
+/// Return the string "Rescue-XLIX(p,w,c,sec)" that seeds the generation of the
+/// round constants, exactly as in the reference implementation: the prime, the
+/// state width, the capacity and the security level in decimal, separated by
+/// commas without spaces.
+fn seed_string(width: usize, capacity: usize, security_level: usize) -> String {
+    format!("Rescue-XLIX({},{},{},{})", PRIME, width, capacity, security_level)
+}
+
+/// Format a row of field elements as a Rust array literal of their values.
+fn emit_row(row: &[FieldElement]) -> String {
+    let values: Vec<String> = row.iter().map(|element| format!("{}", element.value())).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// Check the relationships between the parameters of an instance, before any of
+/// them is used to derive the others.
+fn validate_params(width: usize, rate: usize, security_level: usize) -> Result<(), ParamError> {
+    if width == 0 {
+        return Err(ParamError::UnsupportedWidth);
+    }
+    if rate > width {
+        return Err(ParamError::RateExceedsWidth);
+    }
+    if security_level == 0 || security_level > MAX_SECURITY_LEVEL {
+        return Err(ParamError::InvalidSecurityLevel);
+    }
+    Ok(())
+}
+
+/// Expand the seed string with SHAKE256 into the bytes of the round constants,
+/// BYTES_PER_FIELD bytes for each of the 2 * width * num_rounds constants.
+fn round_constant_bytes(
+    width: usize,
+    capacity: usize,
+    security_level: usize,
+    num_rounds: usize,
+) -> Vec<u8> {
+    let num_bytes = BYTES_PER_FIELD * 2 * width * num_rounds;
+
+    let seed = seed_string(width, capacity, security_level);
+    let mut shake = Shake256::default();
+    shake.update(seed.as_bytes());
+    let mut byte_string = vec![0u8; num_bytes];
+    shake.finalize_xof().read(&mut byte_string);
+    byte_string
+}
+
+/// Reduce the chunks of bytes into the round constants, round by round, as
+/// described in `compute_round_constants`.
+///
+/// Returns an InvalidRoundConstant error if there is not exactly one chunk of
+/// bytes per round constant.
+#[cfg(any(not(feature = "rayon"), test))]
+fn fill_round_constants<const WIDTH: usize>(
+    byte_string: &[u8],
+    round_constants: &mut [Vec<[FieldElement; WIDTH]>; 2],
+) -> Result<(), ParamError> {
+    check_round_constant_bytes(byte_string, round_constants)?;
+
+    let [first, second] = round_constants;
+    let rounds = byte_string.chunks_exact(BYTES_PER_FIELD * 2 * WIDTH);
+    for ((first, second), bytes) in first.iter_mut().zip(second.iter_mut()).zip(rounds) {
+        fill_round::<WIDTH>(bytes, first, second);
+    }
+    Ok(())
+}
+
+/// Reduce the chunks of bytes into the round constants as `fill_round_constants`
+/// does, with the rounds processed in parallel.
+#[cfg(feature = "rayon")]
+fn fill_round_constants_par<const WIDTH: usize>(
+    byte_string: &[u8],
+    round_constants: &mut [Vec<[FieldElement; WIDTH]>; 2],
+) -> Result<(), ParamError> {
+    use rayon::prelude::*;
+
+    check_round_constant_bytes(byte_string, round_constants)?;
+
+    let [first, second] = round_constants;
+    let rounds = byte_string.par_chunks_exact(BYTES_PER_FIELD * 2 * WIDTH);
+    first
+        .par_iter_mut()
+        .zip(second.par_iter_mut())
+        .zip(rounds)
+        .for_each(|((first, second), bytes)| fill_round::<WIDTH>(bytes, first, second));
+    Ok(())
+}
+
+/// Check that the byte string holds exactly one chunk of bytes per round
+/// constant, so that no constant is left out or silently dropped.
+fn check_round_constant_bytes<const WIDTH: usize>(
+    byte_string: &[u8],
+    round_constants: &[Vec<[FieldElement; WIDTH]>; 2],
+) -> Result<(), ParamError> {
+    let [first, second] = round_constants;
+    let num_constants = WIDTH * (first.len() + second.len());
+    if first.len() != second.len() || byte_string.len() != BYTES_PER_FIELD * num_constants {
+        return Err(ParamError::InvalidRoundConstant);
+    }
+    Ok(())
+}
+
+/// Reduce the 2 * WIDTH chunks of bytes of a single round into its two sets of
+/// constants. The length of the bytes is checked by the callers, with
+/// `check_round_constant_bytes`.
+#[inline(always)]
+fn fill_round<const WIDTH: usize>(
+    bytes: &[u8],
+    first: &mut [FieldElement; WIDTH],
+    second: &mut [FieldElement; WIDTH],
+) {
+    let (chunks, remainder) = bytes.as_chunks::<BYTES_PER_FIELD>();
+    debug_assert!(chunks.len() == 2 * WIDTH && remainder.is_empty());
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let constant = round_constant_from_bytes(chunk);
+        if i < WIDTH {
+            first[i] = constant;
+        } else {
+            second[i - WIDTH] = constant;
+        }
+    }
+}
+
+/// Reduce a chunk of bytes, read as an integer with the least significant byte
+/// first, into a round constant with `bytes_to_field_wide`. The chunk always
+/// fits in the u128 that is reduced, as BYTES_PER_FIELD is at most 16.
+fn round_constant_from_bytes(chunk: &[u8; BYTES_PER_FIELD]) -> FieldElement {
+    bytes_to_field_wide(chunk)
+}
+
+/// Return the greatest common divisor of a and b.
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Return the binomial coefficient n choose k, approximated as a float since it
+/// quickly grows beyond the range of the integer types.
+fn binomial(n: usize, k: usize) -> f64 {
+    let k = k.min(n - k);
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
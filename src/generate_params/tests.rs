@@ -0,0 +1,351 @@
+use super::{
+    alpha_inverse, compute_round_constants, emit_params_rust, generate_mds, mds_fingerprint,
+    num_rounds, round_constant_from_bytes, seed_string, smallest_alpha, validate_mds,
+    BYTES_PER_FIELD, MAX_MDS_WIDTH, MAX_SECURITY_LEVEL,
+};
+use crate::{
+    field_element::{FieldElement, ALPHA, ALPHA_INV, PRIME},
+    hash::{Digest, CAPACITY, NUM_ROUNDS, SECURITY_LEVEL, WIDTH},
+    rescue::invert_matrix,
+    utils::{errors::ParamError, test_utils::test_elements},
+};
+
+fn mat_mul<const N: usize>(
+    a: &[[FieldElement; N]; N],
+    b: &[[FieldElement; N]; N],
+) -> [[FieldElement; N]; N] {
+    let mut result = [[FieldElement::ZERO; N]; N];
+    for i in 0..N {
+        for j in 0..N {
+            for k in 0..N {
+                result[i][j] += a[i][k] * b[k][j];
+            }
+        }
+    }
+    result
+}
+
+fn identity<const N: usize>() -> [[FieldElement; N]; N] {
+    let mut result = [[FieldElement::ZERO; N]; N];
+    for (i, row) in result.iter_mut().enumerate() {
+        row[i] = FieldElement::ONE;
+    }
+    result
+}
+
+#[test]
+fn test_generate_mds_reference() {
+    // values computed with the reference Rescue-Prime construction
+    let mds = generate_mds::<4>().unwrap();
+    let expected = [
+        [18446744069414466672u64, 137200, 18446744069414564371, 400],
+        [18446744069367524721, 54762351, 18446744069406741521, 140050],
+        [18446744052937841871, 19167800400, 18446744066675349172, 48177200],
+        [18446738401415181521, 6593435097550, 18446743127447244721, 16531644851],
+    ]
+    .map(|row| row.map(FieldElement::new));
+    assert_eq!(mds, expected);
+
+    let mds = generate_mds::<12>().unwrap();
+    let expected_first_row = [
+        2108866337646019936u64,
+        11223275256334781131,
+        2318414738826783588,
+        11240468238955543594,
+        8007389560317667115,
+        11080831380224887131,
+        3922954383102346493,
+        17194066286743901609,
+        152620255842323114,
+        7203302445933022224,
+        17781531460838764471,
+        2306881200,
+    ]
+    .map(FieldElement::new);
+    assert_eq!(mds[0], expected_first_row);
+}
+
+#[test]
+fn test_generate_mds_invertible() {
+    let mds = generate_mds::<4>().unwrap();
+    let mds_inv = invert_matrix(&mds).expect("the MDS matrix must be invertible");
+    assert_eq!(mat_mul(&mds, &mds_inv), identity());
+
+    let mds = generate_mds::<12>().unwrap();
+    let mds_inv = invert_matrix(&mds).expect("the MDS matrix must be invertible");
+    assert_eq!(mat_mul(&mds, &mds_inv), identity());
+    assert_eq!(mat_mul(&mds_inv, &mds), identity());
+}
+
+#[test]
+fn test_compute_round_constants() {
+    // values computed with the reference Rescue-Prime implementation for the
+    // 128-bit instance with state width 12, rate 8 and 8 rounds
+    let round_constants = compute_round_constants::<12, 8, 8>(128).unwrap();
+    let expected = [
+        16089809142501829443u64,
+        3960375389654894755,
+        2341987601489900096,
+        16513505200733590422,
+    ]
+    .map(FieldElement::new);
+    assert_eq!(round_constants[0][0][..4], expected);
+
+    let expected = [10659391161334081468u64, 6658732499907968660].map(FieldElement::new);
+    assert_eq!(round_constants[1][0][..2], expected);
+
+    let expected = [9887604593884716994u64, 1641991848588750522].map(FieldElement::new);
+    assert_eq!(round_constants[0][7][..2], expected);
+
+    let expected = [15883259392726441221u64, 11205339735648717165].map(FieldElement::new);
+    assert_eq!(round_constants[1][7][10..], expected);
+
+    // a smaller instance with state width 4, rate 2 and 3 rounds
+    let expected = [
+        8447486037723224550u64,
+        17229046436909339019,
+        17663397634813509641,
+        5867697863117697021,
+    ]
+    .map(FieldElement::new);
+    assert_eq!(compute_round_constants::<4, 2, 3>(128).unwrap()[0][0], expected);
+
+    // the constants must be deterministic
+    assert_eq!(round_constants, compute_round_constants::<12, 8, 8>(128).unwrap());
+
+    // and depend on the parameters
+    assert_ne!(round_constants, compute_round_constants::<12, 8, 8>(160).unwrap());
+}
+
+#[test]
+fn test_num_rounds() {
+    // values computed with the reference Rescue-Prime implementation
+    assert_eq!(num_rounds(128, 12, 4, 7), 8);
+    assert_eq!(num_rounds(160, 12, 4, 7), 8);
+    assert_eq!(num_rounds(128, 8, 4, 7), 8);
+    assert_eq!(num_rounds(256, 16, 8, 7), 8);
+    assert_eq!(num_rounds(128, 3, 1, 3), 18);
+    assert_eq!(num_rounds(256, 4, 2, 3), 26);
+
+    // the standard instance uses the derived number of rounds
+    assert_eq!(num_rounds(SECURITY_LEVEL, WIDTH, CAPACITY, ALPHA), NUM_ROUNDS);
+
+    // the largest security level still gives a finite target
+    assert!(num_rounds(MAX_SECURITY_LEVEL, 3, 1, 3) <= 36);
+}
+
+#[test]
+fn test_alpha() {
+    assert_eq!(smallest_alpha(), ALPHA);
+    assert_eq!(alpha_inverse(ALPHA), ALPHA_INV);
+    assert_eq!(alpha_inverse(ALPHA_INV), ALPHA);
+
+    for x in test_elements(16, 0) {
+        assert_eq!(x.exp_u64(ALPHA).exp_u64(ALPHA_INV), x);
+        assert_eq!(x.exp_u64(ALPHA_INV).exp_u64(ALPHA), x);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_alpha_inverse_not_coprime() {
+    alpha_inverse(3);
+}
+
+#[test]
+fn test_seed_string() {
+    assert_eq!(seed_string(12, 4, 128), "Rescue-XLIX(18446744069414584321,12,4,128)");
+    assert_eq!(seed_string(4, 2, 160), "Rescue-XLIX(18446744069414584321,4,2,160)");
+}
+
+#[test]
+fn test_bytes_per_field() {
+    // ceil(64 / 8) + 1 for the 64-bit Goldilocks prime
+    assert_eq!(BYTES_PER_FIELD, 9);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_round_constants_parallel() {
+    use super::{
+        fill_round_constants, fill_round_constants_par, generate_round_constants,
+        round_constant_bytes,
+    };
+
+    // a medium instance with state width 24 and 30 rounds
+    const WIDTH: usize = 24;
+    let byte_string = round_constant_bytes(WIDTH, 8, 256, 30);
+
+    let empty = || [vec![[FieldElement::ZERO; WIDTH]; 30], vec![[FieldElement::ZERO; WIDTH]; 30]];
+    let (mut sequential, mut parallel) = (empty(), empty());
+    fill_round_constants(&byte_string, &mut sequential).unwrap();
+    fill_round_constants_par(&byte_string, &mut parallel).unwrap();
+    assert_eq!(sequential, parallel);
+    assert_eq!(generate_round_constants::<WIDTH>(8, 256, 30).unwrap(), parallel);
+
+    // both paths reject the same malformed byte strings
+    let short = &byte_string[..byte_string.len() - 1];
+    assert_eq!(
+        fill_round_constants_par(short, &mut parallel),
+        Err(ParamError::InvalidRoundConstant)
+    );
+    assert!(parallel[1][29].iter().all(|&constant| constant != FieldElement::ZERO));
+}
+
+#[test]
+fn test_validate_mds() {
+    assert_eq!(validate_mds(&generate_mds::<4>().unwrap()), Ok(()));
+    assert_eq!(validate_mds(&generate_mds::<12>().unwrap()), Ok(()));
+
+    assert_eq!(validate_mds(&identity::<4>()), Err(ParamError::ZeroMdsEntry));
+    let mut mds = generate_mds::<4>().unwrap();
+    mds[3][1] = FieldElement::ZERO;
+    assert_eq!(validate_mds(&mds), Err(ParamError::ZeroMdsEntry));
+}
+
+#[test]
+fn test_round_constant_from_bytes() {
+    // the largest chunk of BYTES_PER_FIELD bytes is reduced to a canonical value
+    let constant = round_constant_from_bytes(&[0xFF; BYTES_PER_FIELD]);
+    assert_eq!(constant, FieldElement::from_wide((1u128 << (8 * BYTES_PER_FIELD)) - 1));
+    assert!(constant.value() < PRIME);
+
+    // the bytes are read with the least significant one first
+    let mut chunk = [0; BYTES_PER_FIELD];
+    chunk[..8].copy_from_slice(&PRIME.to_le_bytes());
+    assert_eq!(round_constant_from_bytes(&chunk), FieldElement::ZERO);
+    chunk = [0; BYTES_PER_FIELD];
+    chunk[..2].copy_from_slice(&[1, 2]);
+    assert_eq!(round_constant_from_bytes(&chunk), FieldElement::new(0x0201));
+    chunk[BYTES_PER_FIELD - 1] = 1;
+    let expected = FieldElement::from_wide(1 << (8 * (BYTES_PER_FIELD - 1)) | 0x0201);
+    assert_eq!(round_constant_from_bytes(&chunk), expected);
+}
+
+#[test]
+fn test_fill_round_constants_invalid() {
+    use super::{fill_round_constants, generate_round_constants, round_constant_bytes};
+
+    // the generator reduces exactly one chunk of bytes per round constant
+    let byte_string = round_constant_bytes(4, 2, 128, 3);
+    let mut round_constants = generate_round_constants::<4>(2, 128, 3).unwrap();
+    assert_eq!(fill_round_constants(&byte_string, &mut round_constants), Ok(()));
+
+    // an error is returned instead of leaving some constants out
+    let mut fewer = [vec![[FieldElement::ZERO; 4]; 3], vec![[FieldElement::ZERO; 4]; 3]];
+    for len in [0, byte_string.len() - 1, byte_string.len() - BYTES_PER_FIELD] {
+        let result = fill_round_constants(&byte_string[..len], &mut fewer);
+        assert_eq!(result, Err(ParamError::InvalidRoundConstant));
+    }
+    let mut longer = byte_string.clone();
+    longer.extend_from_slice(&[0; BYTES_PER_FIELD]);
+    let result = fill_round_constants(&longer, &mut fewer);
+    assert_eq!(result, Err(ParamError::InvalidRoundConstant));
+}
+
+#[test]
+#[should_panic]
+fn test_num_rounds_capacity_too_large() {
+    num_rounds(128, 4, 5, 7);
+}
+
+#[test]
+#[should_panic]
+fn test_num_rounds_alpha_too_small() {
+    num_rounds(128, 12, 4, 0);
+}
+
+#[test]
+#[should_panic]
+fn test_num_rounds_security_level_too_large() {
+    num_rounds(MAX_SECURITY_LEVEL + 1, 12, 4, 7);
+}
+
+#[test]
+fn test_compute_round_constants_invalid() {
+    assert_eq!(compute_round_constants::<4, 5, 3>(128), Err(ParamError::RateExceedsWidth));
+    assert_eq!(compute_round_constants::<0, 0, 3>(128), Err(ParamError::UnsupportedWidth));
+    assert_eq!(compute_round_constants::<4, 2, 3>(0), Err(ParamError::InvalidSecurityLevel));
+    assert_eq!(
+        compute_round_constants::<4, 2, 3>(MAX_SECURITY_LEVEL + 1),
+        Err(ParamError::InvalidSecurityLevel)
+    );
+
+    // a rate equal to the width leaves no capacity, but the constants are defined
+    assert!(compute_round_constants::<4, 4, 3>(128).is_ok());
+}
+
+/// Return the integers of the literal assigned to the constant `name` in the
+/// emitted source, in order.
+fn parse_const(source: &str, name: &str) -> Vec<u64> {
+    let start = source
+        .find(&format!("pub const {name}:"))
+        .expect("the constant must be declared");
+    let literal = &source[start..];
+    let literal = &literal[literal.find(" = ").unwrap() + 3..];
+    let literal = &literal[..literal.find(';').unwrap()];
+    literal
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_emit_params_rust() {
+    let source = emit_params_rust::<12, 8>(128).unwrap();
+
+    assert_eq!(parse_const(&source, "WIDTH"), [12]);
+    assert_eq!(parse_const(&source, "RATE"), [8]);
+    assert_eq!(parse_const(&source, "NUM_ROUNDS"), [NUM_ROUNDS as u64]);
+    assert_eq!(parse_const(&source, "ALPHA"), [ALPHA]);
+    assert_eq!(parse_const(&source, "ALPHA_INV"), [ALPHA_INV]);
+
+    let mds: Vec<u64> = generate_mds::<12>().unwrap().iter().flatten().map(|x| x.value()).collect();
+    assert_eq!(parse_const(&source, "MDS"), mds);
+
+    let round_constants = compute_round_constants::<12, 8, NUM_ROUNDS>(128).unwrap();
+    let round_constants: Vec<u64> =
+        round_constants.iter().flatten().flatten().map(|x| x.value()).collect();
+    assert_eq!(parse_const(&source, "ROUND_CONSTANTS"), round_constants);
+
+    assert_eq!(emit_params_rust::<4, 5>(128), Err(ParamError::RateExceedsWidth));
+}
+
+#[test]
+fn test_generate_mds_unsupported_width() {
+    assert!(generate_mds::<1>().is_ok());
+    assert!(generate_mds::<MAX_MDS_WIDTH>().is_ok());
+    assert_eq!(generate_mds::<0>(), Err(ParamError::UnsupportedWidth));
+    assert_eq!(generate_mds::<{ MAX_MDS_WIDTH + 1 }>(), Err(ParamError::UnsupportedWidth));
+    assert_eq!(generate_mds::<16>(), Err(ParamError::UnsupportedWidth));
+}
+
+// Checking the widths above 8 takes seconds in release builds and much longer
+// in debug builds; run with `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn test_generate_mds_is_mds() {
+    use crate::rescue::is_mds;
+
+    assert!(is_mds(&generate_mds::<9>().unwrap()));
+    assert!(is_mds(&generate_mds::<10>().unwrap()));
+    assert!(is_mds(&generate_mds::<11>().unwrap()));
+    assert!(is_mds(&generate_mds::<MAX_MDS_WIDTH>().unwrap()));
+}
+
+#[test]
+fn test_mds_fingerprint() {
+    // reproduced by scripts/reference.py; a change here means that every digest
+    // of the standard instance has changed
+    let expected = Digest::new([
+        FieldElement::new(5845856254437866199),
+        FieldElement::new(14295393076088462237),
+        FieldElement::new(8553040399082269538),
+        FieldElement::new(1888891309760260407),
+    ]);
+    assert_eq!(mds_fingerprint::<WIDTH>().unwrap(), expected);
+
+    assert_ne!(mds_fingerprint::<8>().unwrap(), expected);
+    assert_eq!(mds_fingerprint::<0>(), Err(ParamError::UnsupportedWidth));
+}
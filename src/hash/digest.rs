@@ -0,0 +1,98 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, LowerHex, Result as FmtResult, UpperHex};
+
+use crate::{
+    field_element::{bytes_to_elements, elements_to_bytes, FieldElement},
+    utils::errors::FieldError,
+};
+
+// STRUCTS
+// =============================================================================
+
+/// The output of the hash function, made of N field elements.
+///
+/// A digest can be passed around as a single typed value, and converted from
+/// and into the array of its elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Digest<const N: usize>([FieldElement; N]);
+
+// IMPLEMENTATIONS
+// =============================================================================
+
+impl<const N: usize> Digest<N> {
+    /// Create a new digest from its elements.
+    pub const fn new(elements: [FieldElement; N]) -> Self {
+        Self(elements)
+    }
+
+    /// Return the elements of the digest.
+    pub fn as_elements(&self) -> &[FieldElement; N] {
+        &self.0
+    }
+
+    /// Return the elements of the digest, consuming it.
+    pub fn into_elements(self) -> [FieldElement; N] {
+        self.0
+    }
+
+    /// Serialize the digest into 8 * N bytes, as the concatenation of the
+    /// little-endian `to_bytes` serializations of its elements.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        elements_to_bytes(&self.0)
+    }
+
+    /// Deserialize a digest written by `to_bytes`. Return an InvalidLength error
+    /// unless there are exactly 8 * N bytes, and an error if any of the elements
+    /// is not canonical.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FieldError> {
+        if bytes.len() != 8 * N {
+            return Err(FieldError::InvalidLength);
+        }
+        let elements = bytes_to_elements(bytes)?;
+        Ok(Self(elements.try_into().expect("the length was checked")))
+    }
+}
+
+/// Display the digest as the lowercase hexadecimal encoding of `to_bytes`.
+///
+/// Since the elements are serialized in little-endian order, this is not the
+/// concatenation of the `LowerHex` formats of the elements, which print their
+/// values most significant digit first.
+impl<const N: usize> Display for Digest<N> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        LowerHex::fmt(self, f)
+    }
+}
+
+/// Format the digest as the lowercase hexadecimal encoding of `to_bytes`, as
+/// `Display` does.
+impl<const N: usize> LowerHex for Digest<N> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for byte in self.to_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Format the digest as the uppercase hexadecimal encoding of `to_bytes`.
+impl<const N: usize> UpperHex for Digest<N> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for byte in self.to_bytes() {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> From<[FieldElement; N]> for Digest<N> {
+    fn from(elements: [FieldElement; N]) -> Self {
+        Self(elements)
+    }
+}
+
+impl<const N: usize> From<Digest<N>> for [FieldElement; N] {
+    fn from(digest: Digest<N>) -> Self {
+        digest.0
+    }
+}
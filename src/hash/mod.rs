@@ -0,0 +1,730 @@
+use alloc::vec::Vec;
+
+use crate::{
+    field::Field,
+    field_element::{bytes_to_field_wide, FieldElement, PRIME},
+    generate_params::{compute_round_constants, generate_mds},
+    rescue::rescue_xlix,
+    utils::once::OnceBox,
+};
+
+mod digest;
+pub use digest::Digest;
+
+mod rescue_prime;
+pub use rescue_prime::{RescueBuilder, RescuePrime};
+
+#[cfg(feature = "digest")]
+mod rescue_digest;
+#[cfg(feature = "digest")]
+pub use rescue_digest::RescueDigest;
+
+mod vectors;
+use vectors::{HASH_EMPTY, HASH_FULL_BLOCK, MERGE_EMPTY_ZERO};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// =============================================================================
+
+/// The number of field elements in the state of the sponge.
+pub const WIDTH: usize = 12;
+
+/// The number of field elements in the rate portion of the state, i.e. the
+/// number of input elements absorbed per permutation.
+pub const RATE: usize = 8;
+
+/// The number of field elements in the capacity portion of the state.
+pub const CAPACITY: usize = WIDTH - RATE;
+
+/// The number of field elements in a digest.
+pub const DIGEST_SIZE: usize = 4;
+
+/// The number of bytes in a digest serialized by `hash_to_bytes`.
+pub const DIGEST_BYTES: usize = DIGEST_SIZE * 8;
+
+/// The number of bytes packed into each field element by `hash_bytes`.
+const BYTES_PER_ELEMENT: usize = 7;
+
+/// The domain tag of `hash_to_field`, as passed to `hash_with_domain`. It is the
+/// largest tag, to stay apart from the small tags chosen by applications.
+pub const HASH_TO_FIELD_DOMAIN: u64 = PRIME - 1;
+
+/// The security level of the hash function in bits.
+pub const SECURITY_LEVEL: usize = 128;
+
+/// The number of rounds of the Rescue-XLIX permutation, as derived by `num_rounds`
+/// for the parameters above.
+pub const NUM_ROUNDS: usize = 8;
+
+// STANDARD PARAMETERS
+// =============================================================================
+
+/// Return the round constants of the standard instance, as generated by
+/// `compute_round_constants`. They are generated on the first call and cached
+/// for the lifetime of the program.
+pub fn standard_round_constants() -> &'static [[[FieldElement; WIDTH]; NUM_ROUNDS]; 2] {
+    static ROUND_CONSTANTS: OnceBox<[[[FieldElement; WIDTH]; NUM_ROUNDS]; 2]> = OnceBox::new();
+    ROUND_CONSTANTS.get_or_init(|| {
+        compute_round_constants::<WIDTH, RATE, NUM_ROUNDS>(SECURITY_LEVEL)
+            .expect("the standard parameters must be valid")
+    })
+}
+
+/// Return the MDS matrix of the standard instance, as generated by
+/// `generate_mds`. It is generated on the first call and cached for the
+/// lifetime of the program.
+pub fn standard_mds() -> &'static [[FieldElement; WIDTH]; WIDTH] {
+    static MDS: OnceBox<[[FieldElement; WIDTH]; WIDTH]> = OnceBox::new();
+    MDS.get_or_init(|| generate_mds::<WIDTH>().expect("the standard parameters must be valid"))
+}
+
+// STRUCTS
+// =============================================================================
+
+/// An incremental hasher, which absorbs its input over any number of calls to
+/// `update` and produces the same digest as `hash` applied to the concatenation
+/// of all the input.
+///
+/// Cloning a hasher forks it: both copies continue from the input absorbed so
+/// far, and can then be fed different input.
+#[derive(Clone)]
+pub struct RescueHasher {
+    state: [FieldElement; WIDTH],
+    buffer: [FieldElement; RATE],
+    buffer_len: usize,
+}
+
+/// The number of field operations spent by `hash` on an input, as computed by
+/// `op_counts`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpCounts {
+    /// The number of field multiplications, including squarings.
+    pub muls: usize,
+    /// The number of field additions, including those of the MDS products.
+    pub adds: usize,
+    /// The number of elements raised to the power ALPHA or ALPHA_INV.
+    pub sbox_applications: usize,
+    /// The number of calls to the permutation.
+    pub permutations: usize,
+}
+
+// IMPLEMENTATIONS
+// =============================================================================
+
+impl RescueHasher {
+    /// Create a new hasher with an all-zero state.
+    pub fn new() -> Self {
+        Self {
+            state: [FieldElement::ZERO; WIDTH],
+            buffer: [FieldElement::ZERO; RATE],
+            buffer_len: 0,
+        }
+    }
+
+    /// Absorb more input into the hasher.
+    pub fn update(&mut self, data: &[FieldElement]) {
+        for &element in data {
+            // A full buffer is only absorbed once more input arrives, since the
+            // last block must be handled by `finalize`.
+            if self.buffer_len == RATE {
+                self.absorb_buffer();
+            }
+            self.buffer[self.buffer_len] = element;
+            self.buffer_len += 1;
+        }
+    }
+
+    /// Restore the hasher to the state of a new one, discarding everything
+    /// absorbed so far, so that it can be reused for another input.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Pad the input if needed and return the digest of everything absorbed.
+    pub fn finalize(mut self) -> Digest<DIGEST_SIZE> {
+        if self.buffer_len == RATE {
+            self.absorb_buffer();
+        } else {
+            absorb(&mut self.state, &self.buffer[..self.buffer_len]);
+            pad::<FieldElement, WIDTH, RATE>(&mut self.state, self.buffer_len);
+            permute(&mut self.state);
+        }
+
+        Digest::new(squeeze(&self.state))
+    }
+
+    /// Absorb the full buffer into the state and apply the permutation.
+    fn absorb_buffer(&mut self) {
+        absorb(&mut self.state, &self.buffer);
+        permute(&mut self.state);
+        self.buffer_len = 0;
+    }
+}
+
+impl Default for RescueHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reset the hasher to an all-zero state, wiping the absorbed input from the
+/// state and the buffer.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for RescueHasher {
+    fn zeroize(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+        self.buffer_len.zeroize();
+    }
+}
+
+/// Wipe the state and the buffer when the hasher is dropped, so that secret
+/// input does not linger in memory.
+#[cfg(feature = "zeroize")]
+impl Drop for RescueHasher {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for RescueHasher {}
+
+/// An incremental hasher over bytes, which absorbs a byte stream over any number
+/// of calls to `update` and produces the same digest as `hash_bytes` applied to
+/// the concatenation of all the input, serialized as in `hash_to_bytes`.
+///
+/// The bytes are packed into field elements 7 at a time as they arrive, so at
+/// most 7 bytes and RATE elements are buffered whatever the length of the
+/// stream, and the 0x01 end marker of `hash_bytes` is only appended by
+/// `finalize`.
+#[derive(Clone, Default)]
+pub struct RescueByteHasher {
+    hasher: RescueHasher,
+    buffer: [u8; BYTES_PER_ELEMENT],
+    buffer_len: usize,
+}
+
+impl RescueByteHasher {
+    /// Create a new hasher that has not absorbed any bytes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Absorb more bytes into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.buffer[self.buffer_len] = byte;
+            self.buffer_len += 1;
+            if self.buffer_len == BYTES_PER_ELEMENT {
+                self.hasher.update(&[bytes_to_element(&self.buffer)]);
+                self.buffer_len = 0;
+            }
+        }
+    }
+
+    /// Mark the end of the input, pack the last, possibly partial, chunk and
+    /// return the serialized digest of everything absorbed.
+    pub fn finalize(mut self) -> [u8; DIGEST_BYTES] {
+        self.buffer[self.buffer_len] = 1;
+        self.buffer[self.buffer_len + 1..].fill(0);
+        self.hasher.update(&[bytes_to_element(&self.buffer)]);
+
+        digest_to_bytes(&core::mem::take(&mut self.hasher).finalize())
+    }
+}
+
+/// Reset the hasher to a fresh one, wiping the absorbed bytes from the buffers.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for RescueByteHasher {
+    fn zeroize(&mut self) {
+        self.hasher.zeroize();
+        self.buffer.zeroize();
+        self.buffer_len.zeroize();
+    }
+}
+
+/// Wipe the buffers when the hasher is dropped, so that secret input does not
+/// linger in memory.
+#[cfg(feature = "zeroize")]
+impl Drop for RescueByteHasher {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for RescueByteHasher {}
+
+// HASH FUNCTIONS
+// =============================================================================
+
+/// Hash a sequence of field elements with the Rescue-Prime sponge.
+///
+/// The state is laid out as `[rate | capacity]`, with `state[..RATE]` being the
+/// rate portion and `state[RATE..]` the capacity portion, and it starts out as
+/// all zeros. The input is absorbed RATE elements at a time by adding them to
+/// the rate portion, applying the permutation after every block.
+///
+/// This is the variable-length mode of the sponge. If the last block is
+/// incomplete, which includes the empty input, it is padded with a ONE followed
+/// by zeros (10* padding), and ONE is added to the first capacity element before
+/// the final permutation. This capacity marker keeps a padded input from
+/// colliding with an unpadded input whose last block happens to look like the
+/// padded one. Fixed-length inputs, as hashed by `merge`, encode their length in
+/// the first capacity element instead.
+///
+/// The digest is the first DIGEST_SIZE elements of the rate portion, see
+/// `hash_with_size` for other output lengths.
+pub fn hash(input: &[FieldElement]) -> Digest<DIGEST_SIZE> {
+    hash_with_size(input)
+}
+
+/// Hash a sequence of field elements as described in `hash`, and squeeze a
+/// digest of D elements out of the rate portion of the state.
+///
+/// A digest of D elements has about 64 * D bits, so by the birthday bound it
+/// offers at most 32 * D bits of collision resistance, e.g. 128 bits for D = 4
+/// and 160 bits for D = 5. Choosing fewer output elements than the security
+/// level of the instance needs lowers the collision resistance accordingly.
+/// The security is also bounded by the capacity, so a larger digest does not
+/// make the hash stronger than the instance. The digest of D elements is a
+/// prefix of any longer digest of the same input.
+///
+/// # Panics
+/// Panics if D is greater than RATE.
+pub fn hash_with_size<const D: usize>(input: &[FieldElement]) -> Digest<D> {
+    assert!(D <= RATE, "The digest must fit in the rate portion of the state.");
+    Digest::new(sponge_hash::<_, WIDTH, RATE, D>([FieldElement::ZERO; WIDTH], input, permute))
+}
+
+/// Hash a sequence of field elements with the standard 128-bit instance of
+/// Rescue-Prime, with a state of WIDTH = 12 elements, RATE = 8, CAPACITY = 4 and
+/// NUM_ROUNDS = 8 rounds.
+///
+/// This is the entry point that needs no choice of parameters: it is the same as
+/// `hash`, and uses the cached constants of `standard_round_constants` and
+/// `standard_mds`. Other instances can be built with `RescuePrime`.
+pub fn rescue_hash_128(input: &[FieldElement]) -> Digest<DIGEST_SIZE> {
+    hash(input)
+}
+
+/// Hash a sequence of field elements with the sponge described in `hash`,
+/// starting from the given state instead of the all-zero one.
+///
+/// This bypasses the standard initialization of the capacity, so the caller is
+/// responsible for domain separation, e.g. by loading a tag into the capacity as
+/// `hash_with_domain` does. It allows committing to a prefix once and reusing
+/// the state, or chaining hashes by starting from the state of a previous one.
+/// `hash_with_state([FieldElement::ZERO; WIDTH], input)` is the same as
+/// `hash(input)`.
+pub fn hash_with_state(
+    initial: [FieldElement; WIDTH],
+    input: &[FieldElement],
+) -> Digest<DIGEST_SIZE> {
+    Digest::new(sponge_hash::<_, WIDTH, RATE, DIGEST_SIZE>(initial, input, permute))
+}
+
+/// Hash the field elements produced by an iterator with `hash`, without
+/// collecting them first.
+///
+/// The elements are absorbed as they come with a `RescueHasher`, which only
+/// buffers up to RATE of them at a time, so the digest is the same as the one of
+/// the collected elements.
+pub fn hash_iter<I: IntoIterator<Item = FieldElement>>(input: I) -> Digest<DIGEST_SIZE> {
+    let mut hasher = RescueHasher::new();
+    for element in input {
+        hasher.update(&[element]);
+    }
+    hasher.finalize()
+}
+
+/// Hash a sequence of u64 values with `hash`, converting each of them with
+/// `FieldElement::new`.
+///
+/// Values greater than or equal to PRIME are reduced modulo PRIME, so `v` and
+/// `v - PRIME` hash to the same digest. Callers that cannot rule out such values
+/// and need them to be distinct should convert them with
+/// `FieldElement::from_canonical` and hash the result instead.
+pub fn hash_u64(input: &[u64]) -> Digest<DIGEST_SIZE> {
+    hash_iter(input.iter().map(|&value| FieldElement::new(value)))
+}
+
+/// Hash a sequence of elements of any field with the sponge described in `hash`,
+/// built on the Rescue-XLIX permutation with the given parameters.
+///
+/// The state has WIDTH elements, RATE of which make up the rate portion, and the
+/// permutation has N rounds. `hash` is this function over `FieldElement` with
+/// the parameters of the standard instance.
+///
+/// # Panics
+/// Panics unless DIGEST_SIZE <= RATE < WIDTH.
+pub fn hash_with_params<F: Field, const WIDTH: usize, const RATE: usize, const N: usize>(
+    input: &[F],
+    round_constants: &[[[F; WIDTH]; N]; 2],
+    mds: &[[F; WIDTH]; WIDTH],
+) -> [F; DIGEST_SIZE] {
+    assert!(RATE < WIDTH, "The rate must be less than the state width.");
+    assert!(DIGEST_SIZE <= RATE, "The digest must fit in the rate portion of the state.");
+
+    sponge_hash::<F, WIDTH, RATE, DIGEST_SIZE>([F::ZERO; WIDTH], input, |state| {
+        rescue_xlix(state, round_constants, mds)
+    })
+}
+
+/// Hash a sequence of field elements with `hash`, in the domain given by a tag.
+///
+/// The capacity portion of the state is laid out as follows:
+/// - `state[RATE]` receives the domain marker of the padding, see `hash`;
+/// - `state[RATE + 1]` is initialized with the domain tag;
+/// - the remaining capacity elements start out as zeros.
+///
+/// Since the capacity is never written by the input, digests computed in
+/// different domains are independent, e.g. for leaves and internal nodes of a
+/// Merkle tree. The tag 0 is the domain of `hash` itself, and the tag
+/// HASH_TO_FIELD_DOMAIN the one of `hash_to_field`. The tag is reduced modulo
+/// PRIME, so distinct tags must be distinct field elements.
+pub fn hash_with_domain(domain: u64, input: &[FieldElement]) -> Digest<DIGEST_SIZE> {
+    let mut state = [FieldElement::ZERO; WIDTH];
+    state[RATE + 1] = FieldElement::new(domain);
+    hash_with_state(state, input)
+}
+
+/// Hash a sequence of field elements with `hash` and serialize the digest into
+/// DIGEST_BYTES = 32 bytes.
+///
+/// The bytes are the `to_bytes` serializations of the DIGEST_SIZE elements of the
+/// digest, in order, so that bytes 8 * i..8 * (i + 1) hold element i as a
+/// little-endian u64 in canonical form.
+pub fn hash_to_bytes(input: &[FieldElement]) -> [u8; DIGEST_BYTES] {
+    digest_to_bytes(&hash(input))
+}
+
+/// Hash each of the inputs independently with `hash`.
+///
+/// When the `rayon` feature is enabled, the inputs are hashed in parallel. The
+/// digests are the same either way, and are returned in the order of the inputs.
+pub fn hash_many(inputs: &[&[FieldElement]]) -> Vec<Digest<DIGEST_SIZE>> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        inputs.par_iter().map(|input| hash(input)).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        inputs.iter().map(|input| hash(input)).collect()
+    }
+}
+
+/// Hash a byte string with the Rescue-Prime sponge.
+///
+/// The bytes are first packed into field elements as follows:
+/// 1. a single 0x01 byte is appended to the bytes, followed by as few 0x00 bytes
+///    as needed to make the length a multiple of 7;
+/// 2. every consecutive chunk of 7 bytes is read as a little-endian integer,
+///    which is always less than 2^56 and hence a canonical field element.
+///
+/// Since the 0x01 byte marks where the input ends, distinct byte strings always
+/// pack into distinct sequences of field elements. The resulting sequence is
+/// then hashed with `hash`.
+pub fn hash_bytes(bytes: &[u8]) -> Digest<DIGEST_SIZE> {
+    hash(&pack_bytes(bytes))
+}
+
+/// Hash a sequence of field elements into an output of any length, using the
+/// sponge as an extendable-output function.
+///
+/// The input is absorbed and padded exactly as in `hash`. The output is then
+/// squeezed RATE elements at a time from the rate portion of the state, applying
+/// the permutation between consecutive blocks, until out_len elements have been
+/// produced. Hence the first DIGEST_SIZE elements are the digest returned by
+/// `hash`, and outputs of different lengths agree on their common prefix.
+pub fn hash_xof(input: &[FieldElement], out_len: usize) -> Vec<FieldElement> {
+    let state = sponge_absorb::<_, WIDTH, RATE>([FieldElement::ZERO; WIDTH], input, permute);
+    squeeze_xof(state, out_len)
+}
+
+/// Expand a byte seed into count pseudorandom field elements, e.g. for
+/// Fiat-Shamir challenges or randomness beacons.
+///
+/// The seed is packed into field elements as in `hash_bytes` and absorbed as in
+/// `hash_xof`, in the domain HASH_TO_FIELD_DOMAIN of `hash_with_domain`, so that
+/// the output never collides with the digest of the same seed with
+/// `hash_bytes`. Then 2 * count elements are squeezed, and every pair (a, b) of
+/// them is reduced into one element as the 128-bit integer a + 2^64 * b with
+/// `from_wide`. Requesting more elements extends the output without changing
+/// the earlier ones.
+pub fn hash_to_field(seed: &[u8], count: usize) -> Vec<FieldElement> {
+    let mut state = [FieldElement::ZERO; WIDTH];
+    state[RATE + 1] = FieldElement::new(HASH_TO_FIELD_DOMAIN);
+    let state = sponge_absorb::<_, WIDTH, RATE>(state, &pack_bytes(seed), permute);
+
+    squeeze_xof(state, 2 * count)
+        .chunks_exact(2)
+        .map(|pair| {
+            let wide = pair[0].value() as u128 | (pair[1].value() as u128) << 64;
+            FieldElement::from_wide(wide)
+        })
+        .collect()
+}
+
+/// Compute out_len pseudorandom field elements from a key and an input, using
+/// the sponge as a keyed pseudorandom function.
+///
+/// The capacity element `state[RATE + 2]` starts out as ONE, which keeps the PRF
+/// apart from `hash` and `hash_with_domain`, whose states leave it at zero. The
+/// key is absorbed first, always padded with a ONE followed by zeros, even when
+/// its length is a multiple of RATE, so that the boundary between the key and
+/// the input is unambiguous. The input is then absorbed and padded as in `hash`,
+/// and the output is squeezed as in `hash_xof`.
+pub fn prf(key: &[FieldElement], input: &[FieldElement], out_len: usize) -> Vec<FieldElement> {
+    let mut state = [FieldElement::ZERO; WIDTH];
+    state[RATE + 2] = FieldElement::ONE;
+
+    let blocks = key.chunks_exact(RATE);
+    let remainder = blocks.remainder();
+    for block in blocks {
+        absorb(&mut state, block);
+        permute(&mut state);
+    }
+    absorb(&mut state, remainder);
+    state[remainder.len()] += FieldElement::ONE;
+    permute(&mut state);
+
+    let state = sponge_absorb::<_, WIDTH, RATE>(state, input, permute);
+    squeeze_xof(state, out_len)
+}
+
+/// Merge two digests into one with a single application of the permutation,
+/// as needed to compute the internal nodes of a Merkle tree.
+///
+/// This is the fixed-length mode of the sponge. The two digests are written into
+/// the rate portion of an all-zero state, the first one followed by the second
+/// one, and the first capacity element is set to the length of the input, i.e.
+/// 2 * DIGEST_SIZE. Since the input always has the same length, no padding is
+/// applied, and the encoded length keeps the result apart from the digest of the
+/// same elements with `hash`. The order of the digests matters, so
+/// `merge(&[a, b])` is different from `merge(&[b, a])`.
+pub fn merge(values: &[Digest<DIGEST_SIZE>; 2]) -> Digest<DIGEST_SIZE> {
+    merge_with_size(values)
+}
+
+/// Merge two digests of D elements into one as described in `merge`.
+///
+/// The collision resistance of the result is bounded by its size as described
+/// in `hash_with_size`.
+///
+/// # Panics
+/// Panics if two digests of D elements do not fit in the rate portion of the
+/// state, i.e. if 2 * D is greater than RATE.
+pub fn merge_with_size<const D: usize>(values: &[Digest<D>; 2]) -> Digest<D> {
+    Digest::new(sponge_merge::<_, WIDTH, RATE, D>(&values.map(Digest::into_elements), permute))
+}
+
+// SELF-TEST
+// =============================================================================
+
+/// Check the standard `hash` and `merge` against embedded test vectors, and
+/// return false if any digest differs.
+///
+/// This guards against a miscompiled or corrupted table of round constants or
+/// MDS matrix, and unlike the unit tests it ships with the library, so it can be
+/// called at program start, including in release builds. The vectors are the
+/// ones checked by the unit and integration tests.
+pub fn self_test() -> bool {
+    self_test_with(permute)
+}
+
+// OPERATION COUNTS
+// =============================================================================
+
+/// Count the field operations that `hash` performs on an input of `input_len`
+/// elements, with a sponge of the given width and rate and a permutation of the
+/// given number of rounds. Nothing is hashed: the counts follow from the
+/// parameters alone.
+///
+/// The input is absorbed in ceil(input_len / RATE) blocks, or a single padded
+/// block when it is empty, with one permutation per block. Absorbing costs one
+/// addition per input element, and padding two more. Every round of the
+/// permutation applies both S-box layers to the whole state, costing 4
+/// multiplications per element for x^7 and 72 for the addition chain of
+/// ALPHA_INV, then two MDS products of WIDTH^2 multiplications and
+/// WIDTH * (WIDTH - 1) additions each, and two additions of WIDTH constants.
+pub fn op_counts<const WIDTH: usize, const RATE: usize>(
+    rounds: usize,
+    input_len: usize,
+) -> OpCounts {
+    assert!(RATE > 0 && RATE < WIDTH, "The rate must be between 1 and WIDTH - 1.");
+
+    let permutations = if input_len == 0 { 1 } else { input_len.div_ceil(RATE) };
+    let padding_adds = if input_len.is_multiple_of(RATE) && input_len != 0 {
+        0
+    } else {
+        2
+    };
+
+    let round_muls = 76 * WIDTH + 2 * WIDTH * WIDTH;
+    let round_adds = 2 * WIDTH * (WIDTH - 1) + 2 * WIDTH;
+    let total_rounds = permutations * rounds;
+
+    OpCounts {
+        muls: total_rounds * round_muls,
+        adds: input_len + padding_adds + total_rounds * round_adds,
+        sbox_applications: total_rounds * 2 * WIDTH,
+        permutations,
+    }
+}
+
+// HELPER FUNCTIONS
+// =============================================================================
+
+/// Pack a byte string into field elements as described in `hash_bytes`.
+fn pack_bytes(bytes: &[u8]) -> Vec<FieldElement> {
+    let chunks = bytes.chunks_exact(BYTES_PER_ELEMENT);
+    let remainder = chunks.remainder();
+
+    let mut elements: Vec<FieldElement> = chunks.map(bytes_to_element).collect();
+
+    let mut last = [0u8; BYTES_PER_ELEMENT];
+    last[..remainder.len()].copy_from_slice(remainder);
+    last[remainder.len()] = 1;
+    elements.push(bytes_to_element(&last));
+
+    elements
+}
+
+/// Read at most 7 bytes as a little-endian integer with `bytes_to_field_wide`,
+/// which is always less than 2^56 and hence canonical.
+#[inline(always)]
+fn bytes_to_element(bytes: &[u8]) -> FieldElement {
+    bytes_to_field_wide(bytes)
+}
+
+/// Serialize a digest as described in `hash_to_bytes`.
+#[inline(always)]
+fn digest_to_bytes(digest: &Digest<DIGEST_SIZE>) -> [u8; DIGEST_BYTES] {
+    let mut bytes = [0u8; DIGEST_BYTES];
+    for (chunk, element) in bytes.chunks_exact_mut(8).zip(digest.as_elements()) {
+        chunk.copy_from_slice(&element.to_bytes());
+    }
+    bytes
+}
+
+/// Apply the Rescue-XLIX permutation of the standard instance to the state.
+#[inline(always)]
+fn permute(state: &mut [FieldElement; WIDTH]) {
+    rescue_xlix(state, standard_round_constants(), standard_mds());
+}
+
+/// Run the vectors of `self_test` through the standard sponge built on the given
+/// permutation.
+fn self_test_with(permute: impl Fn(&mut [FieldElement; WIDTH]) + Copy) -> bool {
+    let hash = |input: &[FieldElement]| {
+        sponge_hash::<_, WIDTH, RATE, DIGEST_SIZE>([FieldElement::ZERO; WIDTH], input, permute)
+    };
+
+    // the empty input is a single padded block
+    let empty = hash(&[]);
+    // 1, 2, ..., 8 is a single block without padding
+    let full_block = hash(&core::array::from_fn::<_, RATE, _>(|i| FieldElement::new(i as u64 + 1)));
+    // the expected digest is merge(hash([]), hash([0]))
+    let merged =
+        sponge_merge::<_, WIDTH, RATE, DIGEST_SIZE>(&[empty, hash(&[FieldElement::ZERO])], permute);
+
+    empty.map(|x| x.value()) == HASH_EMPTY
+        && full_block.map(|x| x.value()) == HASH_FULL_BLOCK
+        && merged.map(|x| x.value()) == MERGE_EMPTY_ZERO
+}
+
+/// Hash a sequence of field elements as described in `hash`, with a sponge of
+/// the given width and rate built on the given permutation, starting from the
+/// given state.
+fn sponge_hash<F: Field, const WIDTH: usize, const RATE: usize, const D: usize>(
+    state: [F; WIDTH],
+    input: &[F],
+    permute: impl Fn(&mut [F; WIDTH]),
+) -> [F; D] {
+    squeeze(&sponge_absorb::<F, WIDTH, RATE>(state, input, permute))
+}
+
+/// Absorb and pad a sequence of field elements as described in `hash`, and
+/// return the state from which the output is squeezed.
+fn sponge_absorb<F: Field, const WIDTH: usize, const RATE: usize>(
+    mut state: [F; WIDTH],
+    input: &[F],
+    permute: impl Fn(&mut [F; WIDTH]),
+) -> [F; WIDTH] {
+    let blocks = input.chunks_exact(RATE);
+    let remainder = blocks.remainder();
+
+    for block in blocks {
+        absorb(&mut state, block);
+        permute(&mut state);
+    }
+
+    if !remainder.is_empty() || input.is_empty() {
+        absorb(&mut state, remainder);
+        pad::<F, WIDTH, RATE>(&mut state, remainder.len());
+        permute(&mut state);
+    }
+
+    state
+}
+
+/// Merge two digests of D elements as described in `merge`, with a sponge of the
+/// given width and rate built on the given permutation.
+fn sponge_merge<F: Field, const WIDTH: usize, const RATE: usize, const D: usize>(
+    values: &[[F; D]; 2],
+    permute: impl Fn(&mut [F; WIDTH]),
+) -> [F; D] {
+    assert!(2 * D <= RATE, "Two digests must fit in the rate portion of the state.");
+
+    let mut state = [F::ZERO; WIDTH];
+    state[..D].copy_from_slice(&values[0]);
+    state[D..2 * D].copy_from_slice(&values[1]);
+    state[RATE] = F::from_u64((2 * D) as u64);
+    permute(&mut state);
+
+    squeeze(&state)
+}
+
+/// Squeeze out_len elements from the state as described in `hash_xof`.
+fn squeeze_xof(mut state: [FieldElement; WIDTH], out_len: usize) -> Vec<FieldElement> {
+    let mut output = Vec::with_capacity(out_len);
+    loop {
+        let len = (out_len - output.len()).min(RATE);
+        output.extend_from_slice(&state[..len]);
+        if output.len() == out_len {
+            break;
+        }
+        permute(&mut state);
+    }
+    output
+}
+
+/// Add a block of at most RATE elements to the rate portion of the state.
+#[inline(always)]
+fn absorb<F: Field, const WIDTH: usize>(state: &mut [F; WIDTH], block: &[F]) {
+    for (element, &value) in state.iter_mut().zip(block.iter()) {
+        *element += value;
+    }
+}
+
+/// Pad an incomplete last block of `len` elements, that has already been
+/// absorbed, with a ONE followed by zeros, and mark the capacity as padded.
+#[inline(always)]
+fn pad<F: Field, const WIDTH: usize, const RATE: usize>(state: &mut [F; WIDTH], len: usize) {
+    state[len] += F::ONE;
+    state[RATE] += F::ONE;
+}
+
+/// Read a digest of D elements out of the rate portion of the state.
+#[inline(always)]
+fn squeeze<F: Field, const WIDTH: usize, const D: usize>(state: &[F; WIDTH]) -> [F; D] {
+    let mut digest = [F::ZERO; D];
+    digest.copy_from_slice(&state[..D]);
+    digest
+}
@@ -0,0 +1,41 @@
+use digest::{consts::U32, FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+
+use super::{RescueByteHasher, DIGEST_BYTES};
+
+// The byte output is the digest serialized as in `hash_to_bytes`.
+const _: () = assert!(DIGEST_BYTES == 32);
+
+// STRUCTS
+// =============================================================================
+
+/// A byte-oriented wrapper around the Rescue-Prime sponge, implementing the
+/// traits of the `digest` crate so that it can be used through `digest::Digest`.
+///
+/// The input bytes are packed into field elements exactly as in `hash_bytes`
+/// by a `RescueByteHasher`, and the 32-byte output is the digest serialized as
+/// in `hash_to_bytes`.
+#[derive(Default)]
+pub struct RescueDigest {
+    hasher: RescueByteHasher,
+}
+
+// IMPLEMENTATIONS
+// =============================================================================
+
+impl OutputSizeUser for RescueDigest {
+    type OutputSize = U32;
+}
+
+impl Update for RescueDigest {
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+}
+
+impl FixedOutput for RescueDigest {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.hasher.finalize());
+    }
+}
+
+impl HashMarker for RescueDigest {}
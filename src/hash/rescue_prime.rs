@@ -0,0 +1,190 @@
+use alloc::vec::Vec;
+
+use super::{sponge_hash, sponge_merge, Digest, DIGEST_SIZE, SECURITY_LEVEL};
+use crate::{
+    field_element::{FieldElement, ALPHA},
+    generate_params::{generate_mds, generate_round_constants, num_rounds, MAX_SECURITY_LEVEL},
+    rescue::{apply_rounds, apply_rounds_ntt, circulant_mds_ntt, MdsNtt},
+    utils::errors::ParamError,
+};
+
+// STRUCTS
+// =============================================================================
+
+/// An instance of the Rescue-Prime hash function with a state of WIDTH field
+/// elements, RATE of which make up the rate portion, for a given security level.
+///
+/// The number of rounds, the MDS matrix and the round constants are derived
+/// once when the instance is created, so that they are not regenerated on every
+/// hash. `RescuePrime::<12, 8>::new(128)` is the standard instance used by the
+/// free functions `hash` and `merge`.
+///
+/// If the MDS matrix is circulant and WIDTH is a power of two, its transform is
+/// precomputed with `circulant_mds_ntt` and the linear layers of the permutation
+/// use `apply_mds_ntt` instead of the matrix-vector product. The matrices of
+/// `generate_mds` are never circulant, so the instances of `new`, `with_rounds`
+/// and `RescueBuilder` always use the product: the NTT path is only taken by
+/// instances built in the crate from a circulant matrix, and other permutations
+/// can call `circulant_mds_ntt` and `apply_mds_ntt` directly.
+pub struct RescuePrime<const WIDTH: usize, const RATE: usize> {
+    round_constants: [Vec<[FieldElement; WIDTH]>; 2],
+    mds: [[FieldElement; WIDTH]; WIDTH],
+    mds_ntt: Option<MdsNtt<WIDTH>>,
+}
+
+/// A builder of `RescuePrime` instances, which checks the parameters of the
+/// instance once, in `build`, and returns an error instead of panicking.
+///
+/// The width and the rate are the const parameters of `build` rather than
+/// runtime `width` and `rate` setters, as they are part of the type of the
+/// instance: a zero WIDTH, or a RATE not less than WIDTH, is rejected when
+/// `build` is compiled instead of returning an error. The security level starts
+/// out as the one of the standard instance, e.g.
+/// `RescueBuilder::new().security_level(160).build::<8, 4>()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RescueBuilder {
+    security_level: usize,
+}
+
+// IMPLEMENTATIONS
+// =============================================================================
+
+impl<const WIDTH: usize, const RATE: usize> RescuePrime<WIDTH, RATE> {
+    /// Create a new instance for the given security level in bits, deriving the
+    /// number of rounds with `num_rounds` and generating the MDS matrix and the
+    /// round constants.
+    ///
+    /// # Panics
+    /// Panics unless DIGEST_SIZE <= RATE < WIDTH, or if the security level is
+    /// greater than MAX_SECURITY_LEVEL.
+    pub fn new(security_level: usize) -> Self {
+        assert!(RATE < WIDTH, "The rate must be less than the state width.");
+        Self::with_rounds(security_level, num_rounds(security_level, WIDTH, WIDTH - RATE, ALPHA))
+    }
+
+    /// Create a new instance for the given security level in bits, with the
+    /// given number of rounds instead of the one derived by `num_rounds`.
+    ///
+    /// This is meant for testing and benchmarking reduced-round variants, e.g.
+    /// for algebraic attacks: an instance with fewer rounds than `new` would
+    /// derive does not reach the security level. The round constants are the
+    /// first num_rounds rounds of the ones of the security level, so any number
+    /// of rounds shares its constants with the secure instance.
+    ///
+    /// # Panics
+    /// Panics unless DIGEST_SIZE <= RATE < WIDTH.
+    pub fn with_rounds(security_level: usize, num_rounds: usize) -> Self {
+        assert!(RATE < WIDTH, "The rate must be less than the state width.");
+        assert!(DIGEST_SIZE <= RATE, "The digest must fit in the rate portion of the state.");
+
+        Self::from_parts(
+            generate_round_constants(WIDTH - RATE, security_level, num_rounds)
+                .expect("the round constants of an instance must be valid"),
+            generate_mds().expect("the width of an instance must be supported"),
+        )
+    }
+
+    /// Create an instance from its round constants and MDS matrix, precomputing
+    /// the transform of the matrix if it is circulant.
+    pub(crate) fn from_parts(
+        round_constants: [Vec<[FieldElement; WIDTH]>; 2],
+        mds: [[FieldElement; WIDTH]; WIDTH],
+    ) -> Self {
+        let mds_ntt = circulant_mds_ntt(&mds);
+        Self {
+            round_constants,
+            mds,
+            mds_ntt,
+        }
+    }
+
+    /// Return the number of rounds of the permutation.
+    pub fn num_rounds(&self) -> usize {
+        self.round_constants[0].len()
+    }
+
+    /// Apply the Rescue-XLIX permutation of this instance to the state in place.
+    pub fn permute(&self, state: &mut [FieldElement; WIDTH]) {
+        let [first_constants, second_constants] = &self.round_constants;
+        match &self.mds_ntt {
+            Some(mds_ntt) => apply_rounds_ntt(state, first_constants, second_constants, mds_ntt),
+            None => apply_rounds(state, first_constants, second_constants, &self.mds),
+        }
+    }
+
+    /// Hash a sequence of field elements with the sponge of this instance, as
+    /// described in the free function `hash`.
+    pub fn hash(&self, input: &[FieldElement]) -> Digest<DIGEST_SIZE> {
+        let state = [FieldElement::ZERO; WIDTH];
+        Digest::new(sponge_hash::<_, WIDTH, RATE, DIGEST_SIZE>(state, input, |state| {
+            self.permute(state)
+        }))
+    }
+
+    /// Merge two digests with the permutation of this instance, as described in
+    /// the free function `merge`.
+    ///
+    /// # Panics
+    /// Panics if two digests do not fit in the rate portion of the state.
+    pub fn merge(&self, values: &[Digest<DIGEST_SIZE>; 2]) -> Digest<DIGEST_SIZE> {
+        let values = values.map(Digest::into_elements);
+        Digest::new(sponge_merge::<_, WIDTH, RATE, DIGEST_SIZE>(&values, |state| {
+            self.permute(state)
+        }))
+    }
+}
+
+impl RescueBuilder {
+    /// Create a new builder with the security level of the standard instance.
+    pub fn new() -> Self {
+        Self {
+            security_level: SECURITY_LEVEL,
+        }
+    }
+
+    /// Set the security level in bits, from which the number of rounds and the
+    /// round constants are derived.
+    pub fn security_level(mut self, security_level: usize) -> Self {
+        self.security_level = security_level;
+        self
+    }
+
+    /// Build the instance with a state of WIDTH elements, RATE of which make up
+    /// the rate portion, deriving the number of rounds with `num_rounds` and
+    /// generating the MDS matrix and the round constants.
+    ///
+    /// WIDTH must be non-zero and RATE less than WIDTH, which is checked at
+    /// compile time.
+    ///
+    /// # Errors
+    /// Returns an error if WIDTH is not supported by `generate_mds`, if two
+    /// digests do not fit in RATE elements, as `merge` needs, or if the security
+    /// level is zero or greater than MAX_SECURITY_LEVEL.
+    pub fn build<const WIDTH: usize, const RATE: usize>(
+        &self,
+    ) -> Result<RescuePrime<WIDTH, RATE>, ParamError> {
+        const {
+            assert!(WIDTH > 0, "The state width must be non-zero.");
+            assert!(RATE < WIDTH, "The rate must be less than the state width.");
+        }
+        if 2 * DIGEST_SIZE > RATE {
+            return Err(ParamError::DigestExceedsRate);
+        }
+        if self.security_level == 0 || self.security_level > MAX_SECURITY_LEVEL {
+            return Err(ParamError::InvalidSecurityLevel);
+        }
+
+        let capacity = WIDTH - RATE;
+        let num_rounds = num_rounds(self.security_level, WIDTH, capacity, ALPHA);
+        Ok(RescuePrime::from_parts(
+            generate_round_constants(capacity, self.security_level, num_rounds)?,
+            generate_mds()?,
+        ))
+    }
+}
+
+impl Default for RescueBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
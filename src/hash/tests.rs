@@ -0,0 +1,748 @@
+use super::vectors::{HASH_EMPTY, HASH_FULL_BLOCK, MERGE_EMPTY_ZERO};
+use super::{
+    hash, hash_bytes, hash_iter, hash_many, hash_to_bytes, hash_to_field, hash_u64,
+    hash_with_domain, hash_with_params, hash_with_size, hash_with_state, hash_xof, merge,
+    merge_with_size, op_counts, pack_bytes, prf, rescue_hash_128, self_test, self_test_with,
+    standard_mds, standard_round_constants, Digest, OpCounts, RescueByteHasher, RescueHasher,
+    DIGEST_SIZE, HASH_TO_FIELD_DOMAIN, NUM_ROUNDS, RATE,
+};
+use crate::{
+    field_element::{FieldElement, PRIME},
+    rescue::rescue_xlix,
+    utils::errors::FieldError,
+};
+
+fn to_elements<const N: usize>(values: [u64; N]) -> [FieldElement; N] {
+    values.map(FieldElement::new)
+}
+
+fn sequence(len: u64) -> Vec<FieldElement> {
+    (0..len).map(FieldElement::new).collect()
+}
+
+#[test]
+fn test_hash_empty() {
+    assert_eq!(hash(&[]), Digest::new(to_elements(HASH_EMPTY)));
+}
+
+#[test]
+fn test_hash_short() {
+    let input = to_elements([1, 2, 3]);
+    let expected = Digest::new(to_elements([
+        9649371255944476923,
+        10892497299409426157,
+        271328877605819891,
+        6773305577261812789,
+    ]));
+    assert_eq!(hash(&input), expected);
+}
+
+#[test]
+fn test_hash_one_block() {
+    let expected = Digest::new(to_elements([
+        5487903022809051988,
+        5310722838991035806,
+        8857518235244476208,
+        5610814025993197921,
+    ]));
+    assert_eq!(hash(&sequence(RATE as u64)), expected);
+
+    let input = to_elements([1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(hash(&input), Digest::new(to_elements(HASH_FULL_BLOCK)));
+}
+
+#[test]
+fn test_hash_several_blocks() {
+    let expected = Digest::new(to_elements([
+        14075269760454204443,
+        3313494770225901816,
+        14391055755087463366,
+        18219665111983433110,
+    ]));
+    assert_eq!(hash(&sequence(20)), expected);
+}
+
+#[test]
+fn test_hash_padding() {
+    // an input whose last block is incomplete must not collide with the same
+    // input padded by hand
+    let input = sequence(3);
+    let mut padded = input.clone();
+    padded.push(FieldElement::ONE);
+    padded.resize(RATE, FieldElement::ZERO);
+    assert_ne!(hash(&input), hash(&padded));
+
+    // appending a zero changes the digest
+    let mut extended = input.clone();
+    extended.push(FieldElement::ZERO);
+    assert_ne!(hash(&input), hash(&extended));
+
+    assert_ne!(hash(&[]), Digest::new([FieldElement::ZERO; DIGEST_SIZE]));
+    assert_ne!(hash(&[]), hash(&[FieldElement::ZERO]));
+}
+
+#[test]
+fn test_merge() {
+    let a = hash(&to_elements([1, 2, 3]));
+    let b = hash(&sequence(20));
+
+    let expected = Digest::new(to_elements([
+        4534208498664125497,
+        11990476609297894840,
+        17607905605013472621,
+        5360385790937592166,
+    ]));
+    assert_eq!(merge(&[a, b]), expected);
+
+    let digests = [hash(&[]), hash(&[FieldElement::ZERO])];
+    assert_eq!(merge(&digests), Digest::new(to_elements(MERGE_EMPTY_ZERO)));
+
+    // merge is not commutative
+    let expected = Digest::new(to_elements([
+        16429128515801139547,
+        6238796010738400222,
+        8645866809301408177,
+        2199826905496990024,
+    ]));
+    assert_eq!(merge(&[b, a]), expected);
+    assert_ne!(merge(&[a, b]), merge(&[b, a]));
+}
+
+#[test]
+fn test_merge_length_encoding() {
+    // merging is hashing a fixed-length input of 8 elements, which must differ
+    // from hashing the same 8 elements with the variable-length sponge
+    let a = hash(&to_elements([1, 2, 3]));
+    let b = hash(&sequence(20));
+    let mut input = a.into_elements().to_vec();
+    input.extend_from_slice(b.as_elements());
+    assert_eq!(input.len(), 2 * DIGEST_SIZE);
+    assert_eq!(input.len(), RATE);
+    assert_ne!(merge(&[a, b]), hash(&input));
+
+    // without the length, a full block is absorbed as is
+    let mut state = [FieldElement::ZERO; super::WIDTH];
+    state[..RATE].copy_from_slice(&input);
+    super::permute(&mut state);
+    assert_eq!(hash(&input).as_elements()[..], state[..DIGEST_SIZE]);
+
+    state = [FieldElement::ZERO; super::WIDTH];
+    state[..RATE].copy_from_slice(&input);
+    state[RATE] = FieldElement::new(RATE as u64);
+    super::permute(&mut state);
+    assert_eq!(merge(&[a, b]).as_elements()[..], state[..DIGEST_SIZE]);
+}
+
+#[test]
+fn test_hash_with_size() {
+    for len in [0, 3, 8, 20] {
+        let input = sequence(len);
+        let short = hash_with_size::<4>(&input);
+        let long = hash_with_size::<5>(&input);
+        assert_eq!(short.as_elements()[..], long.as_elements()[..4]);
+        assert_eq!(short, hash(&input));
+    }
+
+    let full = hash_with_size::<RATE>(&sequence(5));
+    assert_eq!(hash_with_size::<1>(&sequence(5)).as_elements()[..], full.as_elements()[..1]);
+}
+
+#[test]
+#[should_panic(expected = "The digest must fit in the rate portion of the state.")]
+fn test_hash_with_size_too_large() {
+    hash_with_size::<{ RATE + 1 }>(&sequence(5));
+}
+
+#[test]
+fn test_merge_with_size() {
+    let a = hash(&to_elements([1, 2, 3]));
+    let b = hash(&sequence(20));
+    assert_eq!(merge_with_size(&[a, b]), merge(&[a, b]));
+
+    // a smaller digest encodes its own length in the capacity
+    let a = hash_with_size::<3>(&to_elements([1, 2, 3]));
+    let b = hash_with_size::<3>(&sequence(20));
+    let mut state = [FieldElement::ZERO; super::WIDTH];
+    state[..3].copy_from_slice(a.as_elements());
+    state[3..6].copy_from_slice(b.as_elements());
+    state[RATE] = FieldElement::new(6);
+    super::permute(&mut state);
+    assert_eq!(merge_with_size(&[a, b]).as_elements()[..], state[..3]);
+}
+
+#[test]
+fn test_hasher() {
+    for len in [0, 1, 7, 8, 9, 16, 20, 45] {
+        let input = sequence(len);
+        let expected = hash(&input);
+
+        for chunk_size in [1, 3, 7, 8, 9, 50] {
+            let mut hasher = RescueHasher::new();
+            for chunk in input.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), expected, "len {len}, chunk size {chunk_size}");
+        }
+
+        // empty updates do not change anything
+        let mut hasher = RescueHasher::new();
+        hasher.update(&[]);
+        hasher.update(&input);
+        hasher.update(&[]);
+        assert_eq!(hasher.finalize(), expected);
+    }
+}
+
+#[test]
+fn test_pack_bytes() {
+    assert_eq!(pack_bytes(&[]), to_elements([1]));
+    assert_eq!(pack_bytes(&[1, 2, 3, 4, 5, 6, 7]), to_elements([1976943448883713, 1]));
+    assert_eq!(pack_bytes(&[1, 2, 3, 4, 5, 6, 7, 8]), to_elements([1976943448883713, 264]));
+
+    // the packing is injective, even for trailing zeros and marker bytes
+    assert_ne!(pack_bytes(&[]), pack_bytes(&[0]));
+    assert_ne!(pack_bytes(&[0]), pack_bytes(&[0, 0]));
+    assert_ne!(pack_bytes(&[1]), pack_bytes(&[]));
+}
+
+#[test]
+fn test_hash_bytes() {
+    let expected = Digest::new(to_elements([
+        17982282394059169929,
+        16158621905928487406,
+        12737923520709178027,
+        15786371143191942239,
+    ]));
+    assert_eq!(hash_bytes(&[]), expected);
+
+    let expected = Digest::new(to_elements([
+        9160462906405753585,
+        12653776632740884108,
+        4960601152499098581,
+        14358996536457956793,
+    ]));
+    assert_eq!(hash_bytes(&[1, 2, 3, 4, 5, 6, 7]), expected);
+
+    let expected = Digest::new(to_elements([
+        13361503143871619983,
+        10629960636028267575,
+        14941236612703108996,
+        1886190996007436199,
+    ]));
+    assert_eq!(hash_bytes(&[1, 2, 3, 4, 5, 6, 7, 8]), expected);
+
+    assert_ne!(hash_bytes(&[0]), hash_bytes(&[]));
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn test_rescue_digest() {
+    use super::RescueDigest;
+    use digest::Digest;
+
+    fn expected(bytes: &[u8]) -> Vec<u8> {
+        hash_bytes(bytes).to_bytes()
+    }
+
+    let input: Vec<u8> = (0..100u8).collect();
+    assert_eq!(RescueDigest::digest(&input).as_slice(), expected(&input).as_slice());
+    assert_eq!(RescueDigest::digest([]).as_slice(), expected(&[]).as_slice());
+
+    // the output does not depend on how the input is split across updates
+    for chunk_size in [1, 6, 7, 8, 13] {
+        let mut hasher = RescueDigest::new();
+        for chunk in input.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize().as_slice(), expected(&input).as_slice());
+    }
+}
+
+#[test]
+fn test_hash_many() {
+    let inputs: Vec<Vec<FieldElement>> = (0..40).map(sequence).collect();
+    let inputs: Vec<&[FieldElement]> = inputs.iter().map(|input| input.as_slice()).collect();
+
+    let expected: Vec<_> = inputs.iter().map(|input| hash(input)).collect();
+    assert_eq!(hash_many(&inputs), expected);
+
+    assert!(hash_many(&[]).is_empty());
+}
+
+#[test]
+fn test_standard_params() {
+    use super::{standard_mds, standard_round_constants, NUM_ROUNDS, SECURITY_LEVEL, WIDTH};
+    use crate::generate_params::{compute_round_constants, generate_mds};
+
+    let fresh = compute_round_constants::<WIDTH, RATE, NUM_ROUNDS>(SECURITY_LEVEL).unwrap();
+    assert_eq!(standard_round_constants(), &fresh);
+    assert_eq!(standard_mds(), &generate_mds::<WIDTH>().unwrap());
+
+    // the cached values are computed once and shared
+    assert!(std::ptr::eq(standard_round_constants(), standard_round_constants()));
+    assert!(std::ptr::eq(standard_mds(), standard_mds()));
+}
+
+#[test]
+fn test_rescue_prime() {
+    use super::{RescuePrime, NUM_ROUNDS, SECURITY_LEVEL, WIDTH};
+    use crate::rescue::rescue_xlix;
+
+    // the standard instance agrees with the free functions
+    let rescue = RescuePrime::<WIDTH, RATE>::new(SECURITY_LEVEL);
+    assert_eq!(rescue.num_rounds(), NUM_ROUNDS);
+
+    for len in [0, 3, 8, 20] {
+        assert_eq!(rescue.hash(&sequence(len)), hash(&sequence(len)));
+    }
+
+    let digests = [hash(&sequence(3)), hash(&sequence(20))];
+    assert_eq!(rescue.merge(&digests), merge(&digests));
+
+    let mut state = [FieldElement::ONE; WIDTH];
+    let mut expected = state;
+    rescue.permute(&mut state);
+    rescue_xlix(&mut expected, super::standard_round_constants(), super::standard_mds());
+    assert_eq!(state, expected);
+
+    // other instances give other digests
+    let other = RescuePrime::<8, 4>::new(SECURITY_LEVEL);
+    assert_ne!(other.hash(&sequence(3)), hash(&sequence(3)));
+    assert_ne!(RescuePrime::<WIDTH, RATE>::new(160).hash(&sequence(3)), hash(&sequence(3)));
+}
+
+#[test]
+fn test_rescue_prime_circulant_mds() {
+    use super::{RescuePrime, SECURITY_LEVEL};
+    use crate::{
+        generate_params::{generate_mds, generate_round_constants},
+        rescue::{apply_mds, apply_mds_ntt, apply_rounds, circulant_mds_ntt},
+        utils::test_utils::test_array,
+    };
+
+    let column: [FieldElement; 8] = test_array(0);
+    let mut mds = [[FieldElement::ZERO; 8]; 8];
+    for (i, row) in mds.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = column[(8 + i - j) % 8];
+        }
+    }
+    let mds_ntt = circulant_mds_ntt(&mds).expect("the matrix is circulant");
+    let round_constants = generate_round_constants::<8>(4, SECURITY_LEVEL, 8).unwrap();
+    let rescue = RescuePrime::<8, 4>::from_parts(round_constants.clone(), mds);
+
+    // the NTT path gives the same linear layer and permutation as the
+    // matrix-vector product
+    for seed in 1..=16 {
+        let state: [FieldElement; 8] = test_array(seed);
+
+        let mut expected = state;
+        let mut actual = state;
+        apply_mds(&mut expected, &mds);
+        apply_mds_ntt(&mut actual, &mds_ntt);
+        assert_eq!(actual, expected);
+
+        let mut expected = state;
+        let mut actual = state;
+        apply_rounds(&mut expected, &round_constants[0], &round_constants[1], &mds);
+        rescue.permute(&mut actual);
+        assert_eq!(actual, expected);
+    }
+
+    // the generated matrices are not circulant, so they keep the product
+    assert!(circulant_mds_ntt(&generate_mds::<8>().unwrap()).is_none());
+}
+
+#[test]
+fn test_rescue_builder() {
+    use super::{RescueBuilder, RescuePrime, NUM_ROUNDS, WIDTH};
+    use crate::{generate_params::MAX_SECURITY_LEVEL, utils::errors::ParamError};
+
+    // the default parameters are those of the standard instance
+    let rescue = RescueBuilder::new().build::<WIDTH, RATE>().unwrap();
+    assert_eq!(rescue.num_rounds(), NUM_ROUNDS);
+    assert_eq!(rescue.hash(&sequence(20)), hash(&sequence(20)));
+
+    let rescue = RescueBuilder::new().security_level(160).build::<12, 8>().unwrap();
+    let expected = RescuePrime::<12, 8>::new(160);
+    assert_eq!(rescue.num_rounds(), expected.num_rounds());
+    assert_eq!(rescue.hash(&sequence(20)), expected.hash(&sequence(20)));
+
+    // a zero width or a rate not less than the width does not compile
+    let builder = RescueBuilder::new();
+    assert_eq!(builder.build::<16, 8>().err(), Some(ParamError::UnsupportedWidth));
+    assert_eq!(builder.build::<12, 2>().err(), Some(ParamError::DigestExceedsRate));
+    // two digests of 4 elements do not fit in a rate of 5, so merge would panic
+    assert_eq!(builder.build::<10, 5>().err(), Some(ParamError::DigestExceedsRate));
+    assert_eq!(
+        builder.security_level(0).build::<12, 8>().err(),
+        Some(ParamError::InvalidSecurityLevel)
+    );
+    assert_eq!(
+        builder.security_level(MAX_SECURITY_LEVEL + 1).build::<12, 8>().err(),
+        Some(ParamError::InvalidSecurityLevel)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_rescue_prime_merge_too_small() {
+    use super::RescuePrime;
+
+    let rescue = RescuePrime::<8, 4>::new(128);
+    rescue.merge(&[Digest::new([FieldElement::ZERO; DIGEST_SIZE]); 2]);
+}
+
+#[test]
+fn test_rescue_prime_with_rounds() {
+    use super::{RescuePrime, NUM_ROUNDS, SECURITY_LEVEL, WIDTH};
+    use crate::{
+        generate_params::{compute_round_constants, generate_mds},
+        rescue::{invert_matrix, rescue_xlix_inverse},
+    };
+
+    let rescue = RescuePrime::<WIDTH, RATE>::with_rounds(SECURITY_LEVEL, NUM_ROUNDS);
+    assert_eq!(rescue.hash(&sequence(20)), hash(&sequence(20)));
+
+    // a single round is undone by the inverse permutation with the same constants
+    let rescue = RescuePrime::<WIDTH, RATE>::with_rounds(SECURITY_LEVEL, 1);
+    assert_eq!(rescue.num_rounds(), 1);
+
+    let round_constants = compute_round_constants::<WIDTH, RATE, 1>(SECURITY_LEVEL).unwrap();
+    let standard = super::standard_round_constants();
+    assert_eq!([round_constants[0][0], round_constants[1][0]], [standard[0][0], standard[1][0]]);
+    let mds_inv = invert_matrix(&generate_mds::<WIDTH>().unwrap()).unwrap();
+    let original: [FieldElement; WIDTH] = sequence(WIDTH as u64).try_into().unwrap();
+
+    let mut state = original;
+    rescue.permute(&mut state);
+    assert_ne!(state, original);
+    rescue_xlix_inverse(&mut state, &round_constants, &mds_inv);
+    assert_eq!(state, original);
+}
+
+#[test]
+fn test_hash_to_bytes() {
+    for len in [0, 3, 8, 20] {
+        let input = sequence(len);
+        let mut expected = Vec::new();
+        for element in hash(&input).as_elements() {
+            expected.extend_from_slice(&element.to_bytes());
+        }
+        assert_eq!(hash_to_bytes(&input).to_vec(), expected);
+    }
+
+    // the first element of the empty digest, little-endian
+    assert_eq!(hash_to_bytes(&[])[..8], HASH_EMPTY[0].to_le_bytes());
+}
+
+#[test]
+fn test_hash_with_domain() {
+    for len in [0, 3, 8, 20] {
+        let input = sequence(len);
+        assert_eq!(hash_with_domain(0, &input), hash(&input));
+
+        let leaf = hash_with_domain(1, &input);
+        let node = hash_with_domain(2, &input);
+        assert_ne!(leaf, hash(&input));
+        assert_ne!(leaf, node);
+    }
+
+    // tags are field elements
+    let input = sequence(3);
+    assert_eq!(hash_with_domain(PRIME + 1, &input), hash_with_domain(1, &input));
+}
+
+#[test]
+fn test_digest() {
+    let digest = Digest::new(to_elements([1, 0x0123456789ABCDEF, PRIME - 1, 0]));
+    let bytes = digest.to_bytes();
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(Digest::from_bytes(&bytes), Ok(digest));
+    assert_eq!(
+        digest.to_string(),
+        "0100000000000000efcdab896745230100000000ffffffff0000000000000000"
+    );
+    assert_eq!(format!("{:x}", digest), digest.to_string());
+    assert_eq!(format!("{:X}", digest), digest.to_string().to_uppercase());
+
+    // a digest is made of exactly N canonical elements
+    assert_eq!(Digest::<4>::from_bytes(&bytes[..24]), Err(FieldError::InvalidLength));
+    assert_eq!(Digest::<3>::from_bytes(&bytes), Err(FieldError::InvalidLength));
+    let mut bytes = bytes;
+    bytes[24..].copy_from_slice(&PRIME.to_le_bytes());
+    assert_eq!(Digest::<4>::from_bytes(&bytes), Err(FieldError::NonCanonical));
+
+    // the digest of the empty input
+    let digest = hash(&[]);
+    assert_eq!(Digest::from_bytes(&digest.to_bytes()), Ok(digest));
+    assert_eq!(
+        digest.to_string(),
+        "b6b34a3fd7538bf7ebf6d8d26473b917b5f393e2ceab20d698ae93d19b0d3f51"
+    );
+    assert_eq!(<[FieldElement; DIGEST_SIZE]>::from(digest), *digest.as_elements());
+    assert_eq!(Digest::from(digest.into_elements()), digest);
+}
+
+#[test]
+fn test_hash_with_params() {
+    use super::{standard_mds, standard_round_constants, NUM_ROUNDS, WIDTH};
+
+    for len in [0, 3, 8, 20] {
+        let input = sequence(len);
+        let round_constants = standard_round_constants();
+        let digest =
+            hash_with_params::<_, WIDTH, RATE, NUM_ROUNDS>(&input, round_constants, standard_mds());
+        assert_eq!(Digest::new(digest), hash(&input));
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_hasher_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut hasher = RescueHasher::new();
+    hasher.update(&sequence(11));
+    hasher.zeroize();
+    assert_eq!(hasher.state, [FieldElement::ZERO; super::WIDTH]);
+    assert_eq!(hasher.buffer, [FieldElement::ZERO; RATE]);
+    assert_eq!(hasher.buffer_len, 0);
+
+    // a zeroized hasher is a fresh one
+    assert_eq!(hasher.finalize(), hash(&[]));
+}
+
+#[test]
+fn test_hash_xof() {
+    for len in [0, 3, 8, 20] {
+        let input = sequence(len);
+        let output = hash_xof(&input, 3 * RATE + 5);
+        assert_eq!(output.len(), 3 * RATE + 5);
+        assert_eq!(output[..DIGEST_SIZE], *hash(&input).as_elements());
+
+        // shorter outputs are prefixes of longer ones
+        for out_len in [0, 1, DIGEST_SIZE, RATE, RATE + 1, 2 * RATE] {
+            assert_eq!(hash_xof(&input, out_len), output[..out_len]);
+        }
+    }
+
+    // every block is squeezed from a new state
+    let output = hash_xof(&sequence(3), 2 * RATE);
+    assert_ne!(output[..RATE], output[RATE..]);
+}
+
+#[test]
+fn test_hash_iter() {
+    let input: Vec<FieldElement> = (0..100u64).map(FieldElement::from).collect();
+    assert_eq!(hash_iter((0..100u64).map(FieldElement::from)), hash(&input));
+
+    for len in [0, 3, 8, 16, 20] {
+        assert_eq!(hash_iter(sequence(len)), hash(&sequence(len)));
+    }
+}
+
+#[test]
+fn test_prf() {
+    let key = to_elements([11, 22, 33, 44]);
+    let other_key = to_elements([11, 22, 33, 45]);
+    let input = sequence(20);
+
+    // deterministic, and shorter outputs are prefixes of longer ones
+    let output = prf(&key, &input, 2 * RATE + 3);
+    assert_eq!(output.len(), 2 * RATE + 3);
+    assert_eq!(prf(&key, &input, 2 * RATE + 3), output);
+    assert_eq!(prf(&key, &input, DIGEST_SIZE), output[..DIGEST_SIZE]);
+    assert!(prf(&key, &input, 0).is_empty());
+
+    // the output depends on the key and on the input
+    assert_ne!(prf(&other_key, &input, RATE), output[..RATE]);
+    assert_ne!(prf(&key, &sequence(19), RATE), output[..RATE]);
+    assert_ne!(prf(&[], &input, RATE), output[..RATE]);
+
+    // the key is kept apart from the input
+    let mut joined = key.to_vec();
+    joined.extend_from_slice(&input);
+    assert_ne!(prf(&[], &joined, RATE), output[..RATE]);
+    assert_ne!(prf(&key[..3], &joined[3..], RATE), output[..RATE]);
+    let full_key = sequence(RATE as u64);
+    assert_ne!(prf(&full_key, &[], RATE), prf(&[], &full_key, RATE));
+
+    // and from plain hashing
+    assert_ne!(prf(&[], &input, RATE), hash_xof(&input, RATE));
+}
+
+#[test]
+fn test_rescue_hash_128() {
+    // values computed with the reference Rescue-Prime implementation for the
+    // standard instance
+    let expected = Digest::new(to_elements([
+        9649371255944476923,
+        10892497299409426157,
+        271328877605819891,
+        6773305577261812789,
+    ]));
+    assert_eq!(rescue_hash_128(&to_elements([1, 2, 3])), expected);
+
+    for len in [0, 8, 20] {
+        assert_eq!(rescue_hash_128(&sequence(len)), hash(&sequence(len)));
+    }
+}
+
+#[test]
+fn test_hasher_reset() {
+    let mut hasher = RescueHasher::new();
+    for len in [0, 5, 8, 20] {
+        hasher.update(&sequence(len + 3));
+        hasher.reset();
+        hasher.update(&sequence(len));
+        assert_eq!(hasher.clone().finalize(), hash(&sequence(len)));
+        hasher.reset();
+    }
+}
+
+#[test]
+fn test_hasher_clone() {
+    let prefix = sequence(11);
+    let mut hasher = RescueHasher::new();
+    hasher.update(&prefix);
+
+    let mut fork = hasher.clone();
+    hasher.update(&to_elements([1, 2]));
+    fork.update(&to_elements([3]));
+
+    let mut input = prefix.clone();
+    input.extend_from_slice(&to_elements([1, 2]));
+    assert_eq!(hasher.finalize(), hash(&input));
+
+    let mut input = prefix;
+    input.extend_from_slice(&to_elements([3]));
+    assert_eq!(fork.finalize(), hash(&input));
+}
+
+#[test]
+fn test_hash_u64() {
+    let expected =
+        hash(&[FieldElement::from(1u64), FieldElement::from(2u64), FieldElement::from(3u64)]);
+    assert_eq!(hash_u64(&[1, 2, 3]), expected);
+    assert_eq!(hash_u64(&[]), hash(&[]));
+
+    // non-canonical values are reduced
+    assert_eq!(hash_u64(&[PRIME + 1, PRIME]), hash_u64(&[1, 0]));
+}
+
+#[test]
+fn test_hash_with_state() {
+    for len in [0, 3, 8, 20] {
+        let input = sequence(len);
+        assert_eq!(hash_with_state([FieldElement::ZERO; super::WIDTH], &input), hash(&input));
+
+        let mut state = [FieldElement::ZERO; super::WIDTH];
+        state[RATE + 1] = FieldElement::new(7);
+        assert_eq!(hash_with_state(state, &input), hash_with_domain(7, &input));
+    }
+
+    // continue from the state after a full block of the input
+    let input = sequence(19);
+    let mut state = [FieldElement::ZERO; super::WIDTH];
+    state[..RATE].copy_from_slice(&input[..RATE]);
+    super::permute(&mut state);
+    assert_eq!(hash_with_state(state, &input[RATE..]), hash(&input));
+}
+
+#[test]
+fn test_byte_hasher() {
+    let bytes: Vec<u8> = (0..1000u32).map(|i| (i * 31 + 7) as u8).collect();
+    let expected = hash_to_bytes(&pack_bytes(&bytes));
+    assert_eq!(expected, super::digest_to_bytes(&hash_bytes(&bytes)));
+
+    for chunk_size in [1, 7, 8, 13, 1000] {
+        let mut hasher = RescueByteHasher::new();
+        for chunk in bytes.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), expected, "chunks of {chunk_size} bytes");
+    }
+
+    // every length of the last partial chunk, including the empty input
+    for len in 0..=15 {
+        let mut hasher = RescueByteHasher::new();
+        hasher.update(&bytes[..len]);
+        assert_eq!(hasher.finalize(), hash_to_bytes(&pack_bytes(&bytes[..len])));
+    }
+}
+
+#[test]
+fn test_hash_to_field() {
+    let seed = b"fiat-shamir transcript";
+    let elements = hash_to_field(seed, 3 * RATE + 1);
+    assert_eq!(elements.len(), 3 * RATE + 1);
+    assert_eq!(hash_to_field(seed, 3 * RATE + 1), elements);
+
+    // the elements are wide reductions of pairs squeezed in their own domain
+    let squeezed = hash_with_domain(HASH_TO_FIELD_DOMAIN, &pack_bytes(seed)).into_elements();
+    let wide = |a: FieldElement, b: FieldElement| a.value() as u128 | (b.value() as u128) << 64;
+    assert_eq!(elements[0], FieldElement::from_wide(wide(squeezed[0], squeezed[1])));
+    assert_eq!(elements[1], FieldElement::from_wide(wide(squeezed[2], squeezed[3])));
+    assert_ne!(elements[..DIGEST_SIZE], *hash_bytes(seed).as_elements());
+    assert_ne!(elements[..DIGEST_SIZE], *hash_xof(&pack_bytes(seed), DIGEST_SIZE));
+
+    // more elements extend the output
+    for count in [0, 1, RATE, RATE + 1] {
+        assert_eq!(hash_to_field(seed, count), elements[..count]);
+    }
+
+    assert_ne!(hash_to_field(b"fiat-shamir transcripT", RATE), elements[..RATE]);
+    assert_ne!(hash_to_field(b"", RATE), hash_to_field(&[0], RATE));
+}
+
+#[test]
+fn test_op_counts() {
+    // WIDTH = 4, RATE = 2 and one round: 76 * 4 + 2 * 16 = 336 multiplications
+    // and 2 * 4 * 3 + 2 * 4 = 32 additions per permutation
+    let expected = OpCounts {
+        muls: 2 * 336,
+        adds: 3 + 2 + 2 * 32,
+        sbox_applications: 16,
+        permutations: 2,
+    };
+    assert_eq!(op_counts::<4, 2>(1, 3), expected);
+
+    // a whole number of blocks is not padded
+    let expected = OpCounts {
+        muls: 2 * 336,
+        adds: 4 + 2 * 32,
+        sbox_applications: 16,
+        permutations: 2,
+    };
+    assert_eq!(op_counts::<4, 2>(1, 4), expected);
+
+    // the empty input is a single padded block
+    let expected = OpCounts {
+        muls: 336,
+        adds: 2 + 32,
+        sbox_applications: 8,
+        permutations: 1,
+    };
+    assert_eq!(op_counts::<4, 2>(1, 0), expected);
+
+    // the standard instance, as counted in the documentation of `rescue_xlix`
+    let counts = op_counts::<12, RATE>(8, 16);
+    assert_eq!(counts.permutations, 2);
+    assert_eq!(counts.muls, 2 * (384 + 6912 + 2304));
+}
+
+#[test]
+fn test_self_test() {
+    assert!(self_test());
+
+    // a single corrupted round constant must be detected
+    let mut round_constants = *standard_round_constants();
+    round_constants[1][NUM_ROUNDS - 1][0] += FieldElement::ONE;
+    assert!(!self_test_with(|state| rescue_xlix(state, &round_constants, standard_mds())));
+
+    // and so must a corrupted MDS entry
+    let mut mds = *standard_mds();
+    mds[3][5] += FieldElement::ONE;
+    assert!(!self_test_with(|state| rescue_xlix(state, standard_round_constants(), &mds)));
+}
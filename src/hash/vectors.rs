@@ -0,0 +1,27 @@
+// Test vectors of the standard instance, shared by `self_test`, the unit tests
+// and tests/vectors.rs, which includes this file. They are self-consistent
+// outputs of this crate, reproduced by scripts/reference.py.
+
+/// The digest of the empty input, which is a single padded block.
+pub(crate) const HASH_EMPTY: [u64; 4] = [
+    17837442933075915702,
+    1709524410435172075,
+    15429521228424278965,
+    5854413003492142744,
+];
+
+/// The digest of 1, 2, ..., 8, which is a single block without padding.
+pub(crate) const HASH_FULL_BLOCK: [u64; 4] = [
+    5908955903267480209,
+    15212473810059979738,
+    9042709763609473737,
+    2459589162890551170,
+];
+
+/// The merge of the digests of the empty input and of the single element 0.
+pub(crate) const MERGE_EMPTY_ZERO: [u64; 4] = [
+    1759008089668004932,
+    3749522781443268434,
+    12441280122287560138,
+    12081557820602809668,
+];
@@ -1,2 +1,40 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod field;
 mod field_element;
+mod generate_params;
+mod hash;
+mod merkle;
+mod quad_field_element;
+mod rescue;
 mod utils;
+
+pub use field::Field;
+pub use field_element::{
+    add_batch, batch_inv, batch_inv_mut, bytes_to_elements, bytes_to_field_wide, elements_to_bytes,
+    inner_product, mul_acc, mul_batch, pow7_state, write_bytes, FieldElement, ALPHA, ALPHA_INV,
+    PRIME,
+};
+pub use generate_params::{
+    alpha_inverse, compute_round_constants, emit_params_rust, generate_mds, mds_fingerprint,
+    num_rounds, smallest_alpha, validate_mds, MAX_MDS_WIDTH, MAX_SECURITY_LEVEL,
+};
+#[cfg(feature = "digest")]
+pub use hash::RescueDigest;
+pub use hash::{
+    hash, hash_bytes, hash_iter, hash_many, hash_to_bytes, hash_to_field, hash_u64,
+    hash_with_domain, hash_with_params, hash_with_size, hash_with_state, hash_xof, merge,
+    merge_with_size, op_counts, prf, rescue_hash_128, self_test, standard_mds,
+    standard_round_constants, Digest, OpCounts, RescueBuilder, RescueByteHasher, RescueHasher,
+    RescuePrime, CAPACITY, DIGEST_BYTES, DIGEST_SIZE, HASH_TO_FIELD_DOMAIN, NUM_ROUNDS, RATE,
+    SECURITY_LEVEL, WIDTH,
+};
+pub use merkle::{merkle_prove, merkle_root, merkle_verify};
+pub use quad_field_element::QuadFieldElement;
+pub use rescue::{
+    apply_inv_sbox, apply_mds_ntt, apply_sbox, bit_reverse, circulant_mds_ntt, determinant, intt,
+    invert_matrix, is_mds, ntt, rescue_xlix, rescue_xlix_inverse, MdsNtt,
+};
+pub use utils::errors::{FieldError, ParamError};
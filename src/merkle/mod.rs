@@ -0,0 +1,144 @@
+use alloc::vec::Vec;
+
+use crate::hash::{merge, Digest, DIGEST_SIZE};
+
+#[cfg(test)]
+mod tests;
+
+// MERKLE TREES
+// =============================================================================
+
+/// Return the root of the balanced binary Merkle tree with the given leaves.
+///
+/// Every level of the tree is computed from the one below by merging adjacent
+/// pairs of nodes with `merge`, the left node first, until a single node is
+/// left. When a level has an odd number of nodes, its last node is duplicated
+/// and merged with itself. A single leaf is its own root.
+///
+/// When the `rayon` feature is enabled, the pairs of every level are merged in
+/// parallel. The root is the same either way.
+///
+/// # Panics
+/// Panics if there are no leaves.
+pub fn merkle_root(leaves: &[Digest<DIGEST_SIZE>]) -> Digest<DIGEST_SIZE> {
+    assert!(!leaves.is_empty(), "A Merkle tree must have at least one leaf.");
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Return the leaf at the given index and its authentication path, i.e. the
+/// sibling of every node on the way from the leaf up to the root of the tree
+/// built by `merkle_root`, starting with the sibling of the leaf.
+///
+/// When a node is the duplicated last node of its level, it is its own sibling.
+/// The path has one node per level below the root, i.e. ceil(log2(n)) nodes for
+/// n leaves.
+///
+/// # Panics
+/// Panics if the index is not less than the number of leaves.
+pub fn merkle_prove(
+    leaves: &[Digest<DIGEST_SIZE>],
+    index: usize,
+) -> (Digest<DIGEST_SIZE>, Vec<Digest<DIGEST_SIZE>>) {
+    assert!(
+        index < leaves.len(),
+        "The index {} is out of range for a tree with {} leaves.",
+        index,
+        leaves.len()
+    );
+
+    let mut path = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut position = index;
+    while level.len() > 1 {
+        let sibling = (position ^ 1).min(level.len() - 1);
+        path.push(level[sibling]);
+        level = next_level(&level);
+        position >>= 1;
+    }
+    (leaves[index], path)
+}
+
+/// Return true if the authentication path proves that the leaf is at the given
+/// index of the tree with the given root and number of leaves, as returned by
+/// `merkle_prove`.
+///
+/// The root is recomputed by merging the leaf with the nodes of the path, in
+/// order. At level i, bit i of the index tells whether the current node is a
+/// right child, in which case it is merged as the second digest.
+///
+/// The index must be less than the number of leaves, and the path must have
+/// exactly ceil(log2(num_leaves)) nodes. Otherwise the proof is rejected: a
+/// shorter path would let an internal node pass for a leaf, and an index past
+/// the last leaf could reuse the proof of a duplicated last node. For the same
+/// reasons, the number of leaves must be known to the verifier, e.g. committed
+/// to along with the root, rather than taken from the proof.
+pub fn merkle_verify(
+    root: &Digest<DIGEST_SIZE>,
+    leaf: &Digest<DIGEST_SIZE>,
+    index: usize,
+    num_leaves: usize,
+    path: &[Digest<DIGEST_SIZE>],
+) -> bool {
+    if index >= num_leaves {
+        return false;
+    }
+    if path.len() != num_leaves.next_power_of_two().trailing_zeros() as usize {
+        return false;
+    }
+
+    let mut node = *leaf;
+    for (i, sibling) in path.iter().enumerate() {
+        node = if (index >> i) & 1 == 0 {
+            merge(&[node, *sibling])
+        } else {
+            merge(&[*sibling, node])
+        };
+    }
+    node == *root
+}
+
+// HELPER FUNCTIONS
+// =============================================================================
+
+/// Merge adjacent pairs of nodes of a level of the tree into the level above,
+/// duplicating the last node if the number of nodes is odd.
+fn next_level(level: &[Digest<DIGEST_SIZE>]) -> Vec<Digest<DIGEST_SIZE>> {
+    #[cfg(feature = "rayon")]
+    {
+        next_level_par(level)
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        next_level_seq(level)
+    }
+}
+
+/// Compute the level above as described in `next_level`, one pair at a time.
+#[cfg(any(not(feature = "rayon"), test))]
+fn next_level_seq(level: &[Digest<DIGEST_SIZE>]) -> Vec<Digest<DIGEST_SIZE>> {
+    level.chunks(2).map(merge_pair).collect()
+}
+
+/// Compute the level above as described in `next_level`, merging the pairs in
+/// parallel.
+#[cfg(feature = "rayon")]
+fn next_level_par(level: &[Digest<DIGEST_SIZE>]) -> Vec<Digest<DIGEST_SIZE>> {
+    use rayon::prelude::*;
+    level.par_chunks(2).map(merge_pair).collect()
+}
+
+/// Merge a pair of adjacent nodes, or the last node of a level with itself.
+#[inline(always)]
+fn merge_pair(pair: &[Digest<DIGEST_SIZE>]) -> Digest<DIGEST_SIZE> {
+    match pair {
+        [left, right] => merge(&[*left, *right]),
+        [last] => merge(&[*last, *last]),
+        _ => unreachable!(),
+    }
+}
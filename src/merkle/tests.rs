@@ -0,0 +1,137 @@
+use super::{merkle_prove, merkle_root, merkle_verify};
+use crate::{
+    field_element::FieldElement,
+    hash::{hash, merge, Digest, DIGEST_SIZE},
+};
+
+fn leaves(n: u64) -> Vec<Digest<DIGEST_SIZE>> {
+    (0..n).map(|i| hash(&[FieldElement::new(i)])).collect()
+}
+
+#[test]
+fn test_merkle_root() {
+    let leaves = leaves(7);
+
+    assert_eq!(merkle_root(&leaves[..1]), leaves[0]);
+    assert_eq!(merkle_root(&leaves[..2]), merge(&[leaves[0], leaves[1]]));
+
+    let left = merge(&[leaves[0], leaves[1]]);
+    let right = merge(&[leaves[2], leaves[3]]);
+    assert_eq!(merkle_root(&leaves[..4]), merge(&[left, right]));
+
+    // the last node of a level with an odd number of nodes is duplicated
+    let right = merge(&[leaves[2], leaves[2]]);
+    assert_eq!(merkle_root(&leaves[..3]), merge(&[left, right]));
+
+    let level = [
+        merge(&[leaves[0], leaves[1]]),
+        merge(&[leaves[2], leaves[3]]),
+        merge(&[leaves[4], leaves[5]]),
+        merge(&[leaves[6], leaves[6]]),
+    ];
+    let level = [merge(&[level[0], level[1]]), merge(&[level[2], level[3]])];
+    assert_eq!(merkle_root(&leaves), merge(&level));
+
+    // the order of the leaves matters
+    assert_ne!(merkle_root(&[leaves[1], leaves[0]]), merkle_root(&leaves[..2]));
+}
+
+#[test]
+#[should_panic]
+fn test_merkle_root_empty() {
+    merkle_root(&[]);
+}
+
+#[test]
+fn test_merkle_proof() {
+    for n in [1, 2, 3, 5, 8] {
+        let leaves = leaves(n);
+        let root = merkle_root(&leaves);
+        for index in 0..n as usize {
+            let (leaf, path) = merkle_prove(&leaves, index);
+            assert_eq!(leaf, leaves[index]);
+            assert_eq!(path.len(), (n as usize).next_power_of_two().trailing_zeros() as usize);
+            let n = n as usize;
+            assert!(merkle_verify(&root, &leaf, index, n, &path), "{n} leaves, index {index}");
+        }
+    }
+}
+
+#[test]
+fn test_merkle_proof_rejected() {
+    let leaves = leaves(8);
+    let root = merkle_root(&leaves);
+    let (leaf, path) = merkle_prove(&leaves, 5);
+    assert_eq!(path.len(), 3);
+
+    // tampered paths
+    for level in 0..path.len() {
+        let mut tampered = path.clone();
+        tampered[level] = leaves[0];
+        assert!(!merkle_verify(&root, &leaf, 5, 8, &tampered));
+    }
+
+    // wrong leaf, index, number of leaves or root
+    assert!(!merkle_verify(&root, &leaves[4], 5, 8, &path));
+    assert!(!merkle_verify(&root, &leaf, 4, 8, &path));
+    assert!(!merkle_verify(&root, &leaf, 8 + 5, 8, &path));
+    assert!(!merkle_verify(&root, &leaf, 5, 16, &path));
+    assert!(!merkle_verify(&root, &leaf, 5, 0, &path));
+    assert!(!merkle_verify(&leaves[0], &leaf, 5, 8, &path));
+
+    // paths of the wrong length
+    assert!(!merkle_verify(&root, &leaf, 5, 8, &path[..2]));
+    let mut extended = path.clone();
+    extended.push(root);
+    assert!(!merkle_verify(&root, &leaf, 5, 8, &extended));
+    assert!(!merkle_verify(&root, &leaf, 0, 8, &[]));
+}
+
+#[test]
+fn test_merkle_proof_internal_node() {
+    let leaves = leaves(8);
+    let root = merkle_root(&leaves);
+
+    // the root is not a leaf of its own tree, whatever the path
+    assert!(!merkle_verify(&root, &root, 0, 8, &[]));
+
+    // nor is an internal node with the upper part of a path
+    let (_, path) = merkle_prove(&leaves, 0);
+    let node = merge(&[leaves[0], leaves[1]]);
+    assert!(merkle_verify(&root, &leaves[0], 0, 8, &path));
+    assert!(!merkle_verify(&root, &node, 0, 8, &path[1..]));
+}
+
+#[test]
+fn test_merkle_proof_odd_tree_out_of_range() {
+    // the last leaf of a 3-leaf tree is duplicated, so its proof would also
+    // reach the root from index 3
+    let leaves = leaves(3);
+    let root = merkle_root(&leaves);
+    let (leaf, path) = merkle_prove(&leaves, 2);
+    assert!(merkle_verify(&root, &leaf, 2, 3, &path));
+    assert!(!merkle_verify(&root, &leaf, 3, 3, &path));
+}
+
+#[test]
+#[should_panic]
+fn test_merkle_prove_out_of_range() {
+    merkle_prove(&leaves(8), 8);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_merkle_root_parallel() {
+    use super::{next_level_par, next_level_seq};
+
+    for n in [1, 3, 1000, 1024] {
+        let mut level = leaves(n);
+        let root = merkle_root(&level);
+        while level.len() > 1 {
+            let next = next_level_seq(&level);
+            assert_eq!(next_level_par(&level), next);
+            level = next;
+        }
+        assert_eq!(level[0], root, "tree with {n} leaves");
+    }
+}
@@ -0,0 +1,154 @@
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::field_element::FieldElement;
+
+#[cfg(test)]
+mod tests;
+
+// STRUCTS
+// =============================================================================
+
+/// An element a0 + a1 * X of the quadratic extension of the field, i.e. of
+/// F_p[X] / (X^2 - NON_RESIDUE). Since NON_RESIDUE is not a square in F_p, the
+/// polynomial X^2 - NON_RESIDUE is irreducible and the quotient is a field with
+/// p^2 elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuadFieldElement {
+    a0: FieldElement,
+    a1: FieldElement,
+}
+
+// IMPLEMENTATIONS
+// =============================================================================
+
+impl QuadFieldElement {
+    /// The value of X^2 in the extension, the QUADRATIC_NON_RESIDUE 7 of the
+    /// base field.
+    pub const NON_RESIDUE: FieldElement = FieldElement::QUADRATIC_NON_RESIDUE;
+
+    /// The additive identity of the extension.
+    pub const ZERO: QuadFieldElement = QuadFieldElement {
+        a0: FieldElement::ZERO,
+        a1: FieldElement::ZERO,
+    };
+
+    /// The multiplicative identity of the extension.
+    pub const ONE: QuadFieldElement = QuadFieldElement {
+        a0: FieldElement::ONE,
+        a1: FieldElement::ZERO,
+    };
+
+    /// Create the element a0 + a1 * X.
+    pub const fn new(a0: FieldElement, a1: FieldElement) -> Self {
+        Self { a0, a1 }
+    }
+
+    /// Return the coefficients [a0, a1] of the element a0 + a1 * X.
+    pub fn coefficients(&self) -> [FieldElement; 2] {
+        [self.a0, self.a1]
+    }
+
+    /// Return the conjugate a0 - a1 * X of the element, i.e. its image under the
+    /// Frobenius automorphism x -> x^p.
+    pub fn conjugate(self) -> Self {
+        Self::new(self.a0, -self.a1)
+    }
+
+    /// Return the norm of the element, i.e. its product with its conjugate
+    /// a0^2 - NON_RESIDUE * a1^2, which is in the base field.
+    pub fn norm(self) -> FieldElement {
+        self.a0.square() - Self::NON_RESIDUE * self.a1.square()
+    }
+
+    /// Return the square of the element.
+    pub fn square(self) -> Self {
+        self * self
+    }
+
+    /// Return the inverse of the element, computed as its conjugate divided by
+    /// its norm, which is non-zero for any non-zero element.
+    ///
+    /// NOTE: Like `FieldElement::inv`, the inverse of zero is only caught in
+    ///       debug builds. In release builds, it is zero.
+    pub fn inv(self) -> Self {
+        debug_assert!(self != Self::ZERO, "The inverse of zero is undefined.");
+
+        let norm_inv = self.norm().inv();
+        Self::new(self.a0 * norm_inv, -self.a1 * norm_inv)
+    }
+}
+
+impl From<FieldElement> for QuadFieldElement {
+    /// Embed the base field in the extension as the constant polynomials.
+    fn from(a0: FieldElement) -> Self {
+        Self::new(a0, FieldElement::ZERO)
+    }
+}
+
+// OPERATOR IMPLEMENTATIONS
+// =============================================================================
+
+impl Add for QuadFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self::new(self.a0 + other.a0, self.a1 + other.a1)
+    }
+}
+
+impl AddAssign for QuadFieldElement {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for QuadFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.a0 - other.a0, self.a1 - other.a1)
+    }
+}
+
+impl SubAssign for QuadFieldElement {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Mul for QuadFieldElement {
+    type Output = Self;
+
+    /// (a0 + a1 * X) * (b0 + b1 * X) = a0 * b0 + NON_RESIDUE * a1 * b1
+    ///                                 + (a0 * b1 + a1 * b0) * X,
+    /// since X^2 = NON_RESIDUE.
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        let a0b0 = self.a0 * other.a0;
+        let a1b1 = self.a1 * other.a1;
+
+        // Karatsuba: a0 * b1 + a1 * b0 = (a0 + a1) * (b0 + b1) - a0 * b0 - a1 * b1.
+        let cross = (self.a0 + self.a1) * (other.a0 + other.a1) - a0b0 - a1b1;
+        Self::new(a0b0 + Self::NON_RESIDUE * a1b1, cross)
+    }
+}
+
+impl MulAssign for QuadFieldElement {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl Neg for QuadFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::new(-self.a0, -self.a1)
+    }
+}
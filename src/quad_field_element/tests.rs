@@ -0,0 +1,77 @@
+use super::QuadFieldElement;
+use crate::{
+    field_element::{FieldElement, PRIME},
+    utils::test_utils::{test_array, test_elements},
+};
+
+fn pseudo_random(seed: u64) -> QuadFieldElement {
+    let [a0, a1] = test_array(seed);
+    QuadFieldElement::new(a0, a1)
+}
+
+#[test]
+fn test_non_residue() {
+    assert_eq!(QuadFieldElement::NON_RESIDUE.legendre(), -1);
+
+    // X^2 = NON_RESIDUE
+    let x = QuadFieldElement::new(FieldElement::ZERO, FieldElement::ONE);
+    assert_eq!(x * x, QuadFieldElement::from(QuadFieldElement::NON_RESIDUE));
+}
+
+#[test]
+fn test_mul() {
+    // (1 + 2X)(3 + 4X) = 3 + 8 * 7 + (4 + 6)X
+    let a = QuadFieldElement::new(FieldElement::new(1), FieldElement::new(2));
+    let b = QuadFieldElement::new(FieldElement::new(3), FieldElement::new(4));
+    assert_eq!(a * b, QuadFieldElement::new(FieldElement::new(59), FieldElement::new(10)));
+
+    for i in 0..16 {
+        let (a, b, c) = (pseudo_random(i), pseudo_random(i + 16), pseudo_random(i + 32));
+        let [a0, a1] = a.coefficients();
+        let [b0, b1] = b.coefficients();
+        let expected = QuadFieldElement::new(
+            a0 * b0 + QuadFieldElement::NON_RESIDUE * a1 * b1,
+            a0 * b1 + a1 * b0,
+        );
+        assert_eq!(a * b, expected);
+        assert_eq!(a * b, b * a);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!(a * QuadFieldElement::ONE, a);
+        assert_eq!(a - a, QuadFieldElement::ZERO);
+        assert_eq!(a + -a, QuadFieldElement::ZERO);
+        assert_eq!(a.square(), a * a);
+    }
+}
+
+#[test]
+fn test_inv() {
+    assert_eq!(QuadFieldElement::ONE.inv(), QuadFieldElement::ONE);
+
+    for i in 0..16 {
+        let x = pseudo_random(i);
+        assert_eq!(x * x.inv(), QuadFieldElement::ONE);
+        assert_eq!(x.inv().inv(), x);
+        assert_eq!(x * x.conjugate(), QuadFieldElement::from(x.norm()));
+    }
+
+    // elements of the base field and multiples of X
+    let x = QuadFieldElement::new(FieldElement::ZERO, FieldElement::new(PRIME - 5));
+    assert_eq!(x * x.inv(), QuadFieldElement::ONE);
+    let x = QuadFieldElement::from(FieldElement::new(12345));
+    assert_eq!(x.inv(), QuadFieldElement::from(FieldElement::new(12345).inv()));
+}
+
+#[test]
+fn test_embedding() {
+    assert_eq!(QuadFieldElement::from(FieldElement::ZERO), QuadFieldElement::ZERO);
+    assert_eq!(QuadFieldElement::from(FieldElement::ONE), QuadFieldElement::ONE);
+
+    for (a, b) in test_elements(16, 0).into_iter().zip(test_elements(16, 1)) {
+        let (qa, qb) = (QuadFieldElement::from(a), QuadFieldElement::from(b));
+        assert_eq!(QuadFieldElement::from(a + b), qa + qb);
+        assert_eq!(QuadFieldElement::from(a - b), qa - qb);
+        assert_eq!(QuadFieldElement::from(a * b), qa * qb);
+        assert_eq!(QuadFieldElement::from(-a), -qa);
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::{vec, vec::Vec};
+
+use crate::field_element::FieldElement;
+
+// MATRIX OPERATIONS
+// =============================================================================
+
+/// Return the inverse of a square matrix, or None if the matrix is singular.
+///
+/// The inverse is computed with Gauss-Jordan elimination: the row operations
+/// that bring the matrix to the identity are applied to the identity alongside,
+/// which turns it into the inverse.
+pub fn invert_matrix<const N: usize>(m: &[[FieldElement; N]; N]) -> Option<[[FieldElement; N]; N]> {
+    let mut a = *m;
+    let mut inv = identity::<N>();
+
+    for col in 0..N {
+        // Any non-zero entry on or below the diagonal can be the pivot. If
+        // there is none, the columns are linearly dependent.
+        let pivot = (col..N).find(|&row| a[row][col] != FieldElement::ZERO)?;
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let pivot_inv = a[col][col].inv();
+        scale_row(&mut a[col], pivot_inv);
+        scale_row(&mut inv[col], pivot_inv);
+
+        // Eliminate the column from every other row.
+        let (a_pivot, inv_pivot) = (a[col], inv[col]);
+        for row in (0..N).filter(|&row| row != col) {
+            let factor = a[row][col];
+            if factor != FieldElement::ZERO {
+                sub_scaled_row(&mut a[row], &a_pivot, factor);
+                sub_scaled_row(&mut inv[row], &inv_pivot, factor);
+            }
+        }
+    }
+
+    Some(inv)
+}
+
+/// Return the determinant of a square matrix, computed with Gaussian elimination.
+pub fn determinant<const N: usize>(m: &[[FieldElement; N]; N]) -> FieldElement {
+    leading_determinant(&mut m.clone(), N)
+}
+
+/// Return whether a square matrix is MDS, i.e. whether every one of its square
+/// submatrices is invertible. This is what guarantees that the linear layer has
+/// the maximal branch number WIDTH + 1.
+///
+/// All the submatrices are checked, and there are binomial(2 * WIDTH, WIDTH) - 1
+/// of them, so this is only practical for small widths.
+///
+/// # Panics
+/// Panics if WIDTH >= 64.
+pub fn is_mds<const WIDTH: usize>(m: &[[FieldElement; WIDTH]; WIDTH]) -> bool {
+    assert!(WIDTH < 64, "The MDS property can only be checked for widths below 64.");
+
+    // A submatrix is given by a set of rows and a set of columns of the same
+    // size, each encoded as a bitmask. Group the bitmasks by size.
+    let mut masks = vec![Vec::new(); WIDTH + 1];
+    for mask in 1..1u64 << WIDTH {
+        masks[mask.count_ones() as usize].push(mask);
+    }
+
+    let mut sub = [[FieldElement::ZERO; WIDTH]; WIDTH];
+    for (size, masks) in masks.iter().enumerate().skip(1) {
+        for &rows in masks {
+            for &cols in masks {
+                let selected_rows = m.iter().enumerate().filter(|(i, _)| rows >> i & 1 == 1);
+                for (sub_row, (_, row)) in sub.iter_mut().zip(selected_rows) {
+                    let selected = row.iter().enumerate().filter(|(j, _)| cols >> j & 1 == 1);
+                    for (entry, (_, &value)) in sub_row.iter_mut().zip(selected) {
+                        *entry = value;
+                    }
+                }
+                if leading_determinant(&mut sub, size) == FieldElement::ZERO {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+// HELPER FUNCTIONS
+// =============================================================================
+
+/// Return the N x N identity matrix.
+fn identity<const N: usize>() -> [[FieldElement; N]; N] {
+    let mut result = [[FieldElement::ZERO; N]; N];
+    for (i, row) in result.iter_mut().enumerate() {
+        row[i] = FieldElement::ONE;
+    }
+    result
+}
+
+/// Return the determinant of the leading k x k submatrix of a, which is
+/// overwritten in the process. Rows are swapped to find non-zero pivots, each
+/// swap flipping the sign of the determinant, and the determinant of the
+/// resulting triangular matrix is the product of its diagonal.
+fn leading_determinant<const N: usize>(a: &mut [[FieldElement; N]; N], k: usize) -> FieldElement {
+    let mut det = FieldElement::ONE;
+    for col in 0..k {
+        let Some(pivot) = (col..k).find(|&row| a[row][col] != FieldElement::ZERO) else {
+            return FieldElement::ZERO;
+        };
+        if pivot != col {
+            a.swap(col, pivot);
+            det = -det;
+        }
+        det *= a[col][col];
+
+        // Eliminate the column from the rows below the pivot.
+        let pivot_inv = a[col][col].inv();
+        let (top, bottom) = a.split_at_mut(col + 1);
+        let pivot_row = &top[col][col..k];
+        for row in bottom[..k - col - 1].iter_mut() {
+            let factor = row[col] * pivot_inv;
+            if factor != FieldElement::ZERO {
+                for (entry, &pivot_entry) in row[col..k].iter_mut().zip(pivot_row.iter()) {
+                    *entry -= factor * pivot_entry;
+                }
+            }
+        }
+    }
+    det
+}
+
+/// Multiply every entry of the row by the factor.
+#[inline(always)]
+fn scale_row<const N: usize>(row: &mut [FieldElement; N], factor: FieldElement) {
+    for entry in row.iter_mut() {
+        *entry *= factor;
+    }
+}
+
+/// Subtract factor * other from the row.
+#[inline(always)]
+fn sub_scaled_row<const N: usize>(
+    row: &mut [FieldElement; N],
+    other: &[FieldElement; N],
+    factor: FieldElement,
+) {
+    for (entry, &other_entry) in row.iter_mut().zip(other.iter()) {
+        *entry -= factor * other_entry;
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{field::Field, field_element::FieldElement};
+
+mod linalg;
+pub use linalg::{determinant, invert_matrix, is_mds};
+
+mod ntt;
+pub use ntt::{bit_reverse, intt, ntt};
+use ntt::{ntt_with_twiddles, stage_twiddles};
+
+#[cfg(test)]
+mod tests;
+
+// PERMUTATION
+// =============================================================================
+
+/// Apply the Rescue-XLIX permutation to the state in place.
+///
+/// Each of the N rounds consists of the following steps:
+/// 1. the S-box layer, raising every element of the state to the power ALPHA;
+/// 2. the linear layer, multiplying the state by the MDS matrix;
+/// 3. the addition of the first set of round constants;
+/// 4. the inverse S-box layer, raising every element to the power ALPHA_INV;
+/// 5. the linear layer again;
+/// 6. the addition of the second set of round constants.
+///
+/// `round_constants[0][i]` and `round_constants[1][i]` are the constants added
+/// in steps 3 and 6 of the i-th round respectively.
+///
+/// Every round costs 4 * WIDTH multiplications for the S-box layer, 72 * WIDTH
+/// for the inverse S-box layer, 2 * WIDTH^2 products for the two linear layers
+/// and 2 * WIDTH additions of constants. For the standard instance with
+/// WIDTH = 12 and 8 rounds, that is 384 + 6912 + 2304 multiplications and 192
+/// additions per permutation, so the inverse S-box layer dominates.
+///
+/// The number of rounds N is the one of the round constants, so reduced-round
+/// variants only need fewer constants, e.g. from `compute_round_constants`.
+///
+/// The permutation works over any field implementing `Field`, with the S-box
+/// exponents of that field; ALPHA and ALPHA_INV of the `field_element` module
+/// are the ones of `FieldElement`.
+pub fn rescue_xlix<F: Field, const WIDTH: usize, const N: usize>(
+    state: &mut [F; WIDTH],
+    round_constants: &[[[F; WIDTH]; N]; 2],
+    mds: &[[F; WIDTH]; WIDTH],
+) {
+    let [first_constants, second_constants] = round_constants;
+    apply_rounds(state, first_constants, second_constants, mds);
+}
+
+/// Apply the rounds of the Rescue-XLIX permutation to the state in place, for a
+/// number of rounds only known at runtime. The i-th round uses the constants
+/// `first_constants[i]` and `second_constants[i]`.
+pub(crate) fn apply_rounds<F: Field, const WIDTH: usize>(
+    state: &mut [F; WIDTH],
+    first_constants: &[[F; WIDTH]],
+    second_constants: &[[F; WIDTH]],
+    mds: &[[F; WIDTH]; WIDTH],
+) {
+    for (first, second) in first_constants.iter().zip(second_constants.iter()) {
+        apply_round(state, first, second, mds);
+    }
+}
+
+/// Apply the inverse of the Rescue-XLIX permutation to the state in place, so
+/// that it undoes `rescue_xlix` called with the same round constants.
+///
+/// The rounds are run in reverse order, and each of them undoes the steps of the
+/// corresponding round of `rescue_xlix` in reverse: subtracting the round
+/// constants, multiplying by the inverse of the MDS matrix, and swapping the
+/// S-box for the inverse S-box and vice versa. `mds_inv` must be the inverse of
+/// the MDS matrix passed to `rescue_xlix`, as computed by `invert_matrix`.
+pub fn rescue_xlix_inverse<F: Field, const WIDTH: usize, const N: usize>(
+    state: &mut [F; WIDTH],
+    round_constants: &[[[F; WIDTH]; N]; 2],
+    mds_inv: &[[F; WIDTH]; WIDTH],
+) {
+    let [first_constants, second_constants] = round_constants;
+    for (first, second) in first_constants.iter().zip(second_constants.iter()).rev() {
+        sub_constants(state, second);
+        apply_mds(state, mds_inv);
+        apply_sbox(state);
+
+        sub_constants(state, first);
+        apply_mds(state, mds_inv);
+        apply_inv_sbox(state);
+    }
+}
+
+// S-BOX LAYERS
+// =============================================================================
+
+/// Apply the S-box layer of the permutation to the state in place, raising
+/// every element to the power ALPHA of the field, with `pow7` for
+/// `FieldElement`.
+#[inline(always)]
+pub fn apply_sbox<F: Field, const WIDTH: usize>(state: &mut [F; WIDTH]) {
+    F::sbox(state);
+}
+
+/// Apply the inverse S-box layer of the permutation to the state in place,
+/// raising every element to the power ALPHA_INV of the field, so that it undoes
+/// `apply_sbox` and vice versa. For `FieldElement`, this is the same as
+/// `exp_u64(ALPHA_INV)` on every element, with an addition chain.
+#[inline(always)]
+pub fn apply_inv_sbox<F: Field, const WIDTH: usize>(state: &mut [F; WIDTH]) {
+    F::inv_sbox(state);
+}
+
+// NTT-BASED LINEAR LAYER
+// =============================================================================
+
+/// The precomputed data used by `apply_mds_ntt` to multiply the state by a
+/// circulant MDS matrix, as returned by `circulant_mds_ntt`: the transform of
+/// the matrix, and the twiddle factors of every stage of the forward and
+/// inverse transforms of WIDTH values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MdsNtt<const WIDTH: usize> {
+    transform: [FieldElement; WIDTH],
+    twiddles: [FieldElement; WIDTH],
+    inv_twiddles: [FieldElement; WIDTH],
+}
+
+/// Precompute the data used by `apply_mds_ntt` for a circulant MDS matrix.
+///
+/// A circulant matrix C, where C[i][j] = c[(i - j) mod WIDTH] for its first
+/// column c, acts on the state as the cyclic convolution of c with the state.
+/// Hence C * x = INTT(NTT(c) * NTT(x)) with a length-WIDTH number theoretic
+/// transform, which takes O(WIDTH log WIDTH) multiplications instead of
+/// O(WIDTH^2). The transform NTT(c) is scaled by 1 / WIDTH, so that no scaling
+/// is needed after the inverse transform, and the twiddle factors of both
+/// transforms are computed here, so that `apply_mds_ntt` only computes the
+/// butterflies and the pointwise product.
+///
+/// Returns None if WIDTH is not a power of two or if the matrix is not
+/// circulant, in which case the naive matrix-vector product must be used.
+pub fn circulant_mds_ntt<const WIDTH: usize>(
+    mds: &[[FieldElement; WIDTH]; WIDTH],
+) -> Option<MdsNtt<WIDTH>> {
+    if !WIDTH.is_power_of_two() || WIDTH.trailing_zeros() > FieldElement::TWO_ADICITY {
+        return None;
+    }
+
+    let mut transform = [FieldElement::ZERO; WIDTH];
+    for (i, entry) in transform.iter_mut().enumerate() {
+        *entry = mds[i][0];
+    }
+
+    let is_circulant =
+        (0..WIDTH).all(|i| (0..WIDTH).all(|j| mds[i][j] == transform[(WIDTH + i - j) % WIDTH]));
+    if !is_circulant {
+        return None;
+    }
+
+    let root = FieldElement::get_root_of_unity(WIDTH.trailing_zeros());
+    let mut twiddles = [FieldElement::ZERO; WIDTH];
+    let mut inv_twiddles = [FieldElement::ZERO; WIDTH];
+    stage_twiddles(&mut twiddles[..WIDTH - 1], root);
+    stage_twiddles(&mut inv_twiddles[..WIDTH - 1], root.inv());
+
+    ntt_with_twiddles(&mut transform, &twiddles[..WIDTH - 1]);
+    let width_inv = FieldElement::new(WIDTH as u64).inv();
+    for entry in transform.iter_mut() {
+        *entry *= width_inv;
+    }
+
+    Some(MdsNtt {
+        transform,
+        twiddles,
+        inv_twiddles,
+    })
+}
+
+/// Replace the state with the product of a circulant MDS matrix and the state,
+/// given the data precomputed by `circulant_mds_ntt` for the matrix.
+pub fn apply_mds_ntt<const WIDTH: usize>(
+    state: &mut [FieldElement; WIDTH],
+    mds_ntt: &MdsNtt<WIDTH>,
+) {
+    ntt_with_twiddles(state, &mds_ntt.twiddles[..WIDTH - 1]);
+    for (element, &entry) in state.iter_mut().zip(mds_ntt.transform.iter()) {
+        *element *= entry;
+    }
+    ntt_with_twiddles(state, &mds_ntt.inv_twiddles[..WIDTH - 1]);
+}
+
+/// Apply the rounds of the Rescue-XLIX permutation to the state in place as
+/// `apply_rounds` does, with a circulant MDS matrix given by its transform as
+/// precomputed by `circulant_mds_ntt`, so that both linear layers of every round
+/// use `apply_mds_ntt`.
+pub(crate) fn apply_rounds_ntt<const WIDTH: usize>(
+    state: &mut [FieldElement; WIDTH],
+    first_constants: &[[FieldElement; WIDTH]],
+    second_constants: &[[FieldElement; WIDTH]],
+    mds_ntt: &MdsNtt<WIDTH>,
+) {
+    for (first, second) in first_constants.iter().zip(second_constants.iter()) {
+        apply_sbox(state);
+        apply_mds_ntt(state, mds_ntt);
+        add_constants(state, first);
+
+        apply_inv_sbox(state);
+        apply_mds_ntt(state, mds_ntt);
+        add_constants(state, second);
+    }
+}
+
+// HELPER FUNCTIONS
+// =============================================================================
+
+/// Apply a single round of the Rescue-XLIX permutation to the state.
+#[inline(always)]
+fn apply_round<F: Field, const WIDTH: usize>(
+    state: &mut [F; WIDTH],
+    first_constants: &[F; WIDTH],
+    second_constants: &[F; WIDTH],
+    mds: &[[F; WIDTH]; WIDTH],
+) {
+    apply_sbox(state);
+    apply_mds(state, mds);
+    add_constants(state, first_constants);
+
+    apply_inv_sbox(state);
+    apply_mds(state, mds);
+    add_constants(state, second_constants);
+}
+
+/// Replace the state with the product of the MDS matrix and the state, with one
+/// `Field::inner_product` per row.
+#[inline(always)]
+pub(crate) fn apply_mds<F: Field, const WIDTH: usize>(
+    state: &mut [F; WIDTH],
+    mds: &[[F; WIDTH]; WIDTH],
+) {
+    let mut result = [F::ZERO; WIDTH];
+    for (res, row) in result.iter_mut().zip(mds.iter()) {
+        *res = F::inner_product(row, state);
+    }
+    *state = result;
+}
+
+/// Add the round constants to the state element-wise.
+#[inline(always)]
+fn add_constants<F: Field, const WIDTH: usize>(state: &mut [F; WIDTH], constants: &[F; WIDTH]) {
+    for (element, &constant) in state.iter_mut().zip(constants.iter()) {
+        *element += constant;
+    }
+}
+
+/// Subtract the round constants from the state element-wise.
+#[inline(always)]
+fn sub_constants<F: Field, const WIDTH: usize>(state: &mut [F; WIDTH], constants: &[F; WIDTH]) {
+    for (element, &constant) in state.iter_mut().zip(constants.iter()) {
+        *element -= constant;
+    }
+}
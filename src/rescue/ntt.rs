@@ -0,0 +1,222 @@
+use crate::field_element::FieldElement;
+
+// NUMBER THEORETIC TRANSFORM
+// =============================================================================
+
+/// Replace the values with their number theoretic transform, i.e. the
+/// evaluations of the polynomial with these coefficients at the powers of the
+/// primitive n-th root of unity w given by `get_root_of_unity`, where n is the
+/// number of values: values[i] becomes the sum of values[j] * w^(i * j).
+///
+/// Both the input and the output are in natural order.
+///
+/// # Panics
+/// Panics if the number of values is not a power of two, or is greater than
+/// 2^TWO_ADICITY.
+pub fn ntt(values: &mut [FieldElement]) {
+    ntt_in_place(values, transform_root(values.len()));
+}
+
+/// Replace the values with their inverse number theoretic transform, so that
+/// `intt` undoes `ntt`: the transform is computed with the inverse root of
+/// unity, and scaled by 1 / n.
+///
+/// # Panics
+/// Panics if the number of values is not a power of two, or is greater than
+/// 2^TWO_ADICITY.
+pub fn intt(values: &mut [FieldElement]) {
+    ntt_in_place(values, transform_root(values.len()).inv());
+
+    let n_inv = FieldElement::new(values.len() as u64).inv();
+    for value in values.iter_mut() {
+        *value *= n_inv;
+    }
+}
+
+/// Reorder the values so that the value at index i moves to the index whose
+/// binary representation, on log2(n) bits, is the one of i reversed, where n is
+/// the number of values. The permutation is its own inverse.
+///
+/// # Panics
+/// Panics if the number of values is not a power of two.
+pub fn bit_reverse<T>(values: &mut [T]) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "The number of values must be a power of two.");
+    if n == 1 {
+        return;
+    }
+
+    let log_n = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// =============================================================================
+
+/// Return the primitive n-th root of unity used by the transforms of n values.
+fn transform_root(n: usize) -> FieldElement {
+    assert!(n.is_power_of_two(), "The number of values must be a power of two.");
+    FieldElement::get_root_of_unity(n.trailing_zeros())
+}
+
+/// Compute the number theoretic transform of the values in place, using the
+/// given primitive n-th root of unity, where n is the number of values and a
+/// power of two. The transform is computed with the iterative radix-2
+/// Cooley-Tukey algorithm, starting with a bit-reversal permutation, so that
+/// both the input and the output are in natural order.
+///
+/// When the `rayon` feature is enabled, transforms of at least
+/// PARALLEL_THRESHOLD values are computed in parallel. The output is the same
+/// either way.
+fn ntt_in_place(values: &mut [FieldElement], root: FieldElement) {
+    #[cfg(feature = "rayon")]
+    if values.len() >= PARALLEL_THRESHOLD {
+        ntt_in_place_par(values, root);
+        return;
+    }
+
+    ntt_in_place_seq(values, root);
+}
+
+/// The number of values from which the transform is computed in parallel, below
+/// which the overhead of the threads outweighs the gain.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 1 << 16;
+
+/// The number of butterflies of a block computed by a single task of the
+/// parallel transform.
+#[cfg(feature = "rayon")]
+const BUTTERFLIES_PER_TASK: usize = 1 << 10;
+
+/// Compute the transform as described in `ntt_in_place`, one block at a time.
+pub(super) fn ntt_in_place_seq(values: &mut [FieldElement], root: FieldElement) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+
+    bit_reverse(values);
+
+    let mut len = 2;
+    while len <= n {
+        // A primitive len-th root of unity.
+        let step = root.exp_u64((n / len) as u64);
+        for block in values.chunks_exact_mut(len) {
+            let (low, high) = block.split_at_mut(len / 2);
+            butterflies(low, high, FieldElement::ONE, step);
+        }
+        len <<= 1;
+    }
+}
+
+/// Compute the transform as described in `ntt_in_place`, with the butterflies
+/// of every stage split across threads.
+///
+/// The blocks of a stage are independent, and so are the butterflies within a
+/// block, so every task computes about BUTTERFLIES_PER_TASK butterflies: in the
+/// early stages, which have many small blocks, a task takes several whole
+/// blocks, and in the late ones, which have a few large blocks, it takes a chunk
+/// of a block starting from its own power of the twiddle factor.
+#[cfg(feature = "rayon")]
+pub(super) fn ntt_in_place_par(values: &mut [FieldElement], root: FieldElement) {
+    use rayon::prelude::*;
+
+    let n = values.len();
+    bit_reverse(values);
+
+    let mut len = 2;
+    while len <= n {
+        // A primitive len-th root of unity.
+        let step = root.exp_u64((n / len) as u64);
+        if len / 2 <= BUTTERFLIES_PER_TASK {
+            values.par_chunks_mut(2 * BUTTERFLIES_PER_TASK).for_each(|blocks| {
+                for block in blocks.chunks_exact_mut(len) {
+                    let (low, high) = block.split_at_mut(len / 2);
+                    butterflies(low, high, FieldElement::ONE, step);
+                }
+            });
+        } else {
+            values.par_chunks_exact_mut(len).for_each(|block| {
+                let (low, high) = block.split_at_mut(len / 2);
+                let chunks = low
+                    .par_chunks_mut(BUTTERFLIES_PER_TASK)
+                    .zip(high.par_chunks_mut(BUTTERFLIES_PER_TASK));
+                chunks.enumerate().for_each(|(i, (low, high))| {
+                    let twiddle = step.exp_u64((i * BUTTERFLIES_PER_TASK) as u64);
+                    butterflies(low, high, twiddle, step);
+                });
+            });
+        }
+        len <<= 1;
+    }
+}
+
+/// Fill the twiddle factors of every stage of a transform of n values with the
+/// given primitive n-th root of unity, for `ntt_with_twiddles`: the stage with
+/// blocks of 2h values uses the h factors starting at index h - 1, the k-th of
+/// which is w^k for the primitive 2h-th root of unity w. There are n - 1 factors
+/// in total.
+pub(super) fn stage_twiddles(twiddles: &mut [FieldElement], root: FieldElement) {
+    let n = twiddles.len() + 1;
+    let mut half = 1;
+    while half < n {
+        // A primitive (2 * half)-th root of unity.
+        let step = root.exp_u64((n / (2 * half)) as u64);
+        let mut twiddle = FieldElement::ONE;
+        for entry in &mut twiddles[half - 1..2 * half - 1] {
+            *entry = twiddle;
+            twiddle *= step;
+        }
+        half <<= 1;
+    }
+}
+
+/// Compute the transform as described in `ntt_in_place`, with the twiddle
+/// factors of every stage precomputed by `stage_twiddles` instead of being
+/// derived from the root of unity, for small transforms computed many times.
+pub(super) fn ntt_with_twiddles(values: &mut [FieldElement], twiddles: &[FieldElement]) {
+    let n = values.len();
+    debug_assert_eq!(twiddles.len() + 1, n.max(1));
+    if n <= 1 {
+        return;
+    }
+
+    bit_reverse(values);
+
+    let mut half = 1;
+    while half < n {
+        let stage = &twiddles[half - 1..2 * half - 1];
+        for block in values.chunks_exact_mut(2 * half) {
+            let (low, high) = block.split_at_mut(half);
+            for ((u, v), &twiddle) in low.iter_mut().zip(high.iter_mut()).zip(stage) {
+                let t = *v * twiddle;
+                *v = *u - t;
+                *u += t;
+            }
+        }
+        half <<= 1;
+    }
+}
+
+/// Apply the butterflies between the two halves of a block, or of a chunk of a
+/// block, where the first butterfly uses the given twiddle factor and each of
+/// the next ones the previous twiddle factor times step.
+#[inline(always)]
+fn butterflies(
+    low: &mut [FieldElement],
+    high: &mut [FieldElement],
+    mut twiddle: FieldElement,
+    step: FieldElement,
+) {
+    for (u, v) in low.iter_mut().zip(high.iter_mut()) {
+        let t = *v * twiddle;
+        *v = *u - t;
+        *u += t;
+        twiddle *= step;
+    }
+}
@@ -0,0 +1,408 @@
+use super::{
+    apply_inv_sbox, apply_mds, apply_mds_ntt, apply_sbox, bit_reverse, circulant_mds_ntt,
+    determinant, intt, invert_matrix, is_mds, ntt, rescue_xlix, rescue_xlix_inverse,
+};
+use crate::{
+    field_element::{FieldElement, ALPHA, ALPHA_INV, PRIME},
+    utils::test_utils::{test_array, test_elements},
+};
+
+const WIDTH: usize = 4;
+const N: usize = 3;
+
+/// A small circulant matrix and a sequence of simple round constants, which
+/// are enough to pin down the behaviour of the permutation.
+fn test_params() -> ([[[FieldElement; WIDTH]; N]; 2], [[FieldElement; WIDTH]; WIDTH]) {
+    let mut round_constants = [[[FieldElement::ZERO; WIDTH]; N]; 2];
+    for (d, constants) in round_constants.iter_mut().enumerate() {
+        for (r, round) in constants.iter_mut().enumerate() {
+            for (i, constant) in round.iter_mut().enumerate() {
+                *constant = FieldElement::new((d * N * WIDTH + r * WIDTH + i + 1) as u64);
+            }
+        }
+    }
+
+    let row = [2u64, 3, 1, 1];
+    let mut mds = [[FieldElement::ZERO; WIDTH]; WIDTH];
+    for (i, mds_row) in mds.iter_mut().enumerate() {
+        for (j, entry) in mds_row.iter_mut().enumerate() {
+            *entry = FieldElement::new(row[(WIDTH + j - i) % WIDTH]);
+        }
+    }
+
+    (round_constants, mds)
+}
+
+#[test]
+fn test_alpha() {
+    // ALPHA * ALPHA_INV = 1 (mod PRIME - 1)
+    let product = (ALPHA as u128 * ALPHA_INV as u128) % (PRIME as u128 - 1);
+    assert_eq!(product, 1);
+}
+
+#[test]
+fn test_apply_inv_sbox() {
+    let mut state = test_array::<32>(3);
+    state[0] = FieldElement::ZERO;
+    state[1] = FieldElement::ONE;
+    let original = state;
+
+    apply_inv_sbox(&mut state);
+    for (&x, &y) in original.iter().zip(state.iter()) {
+        assert_eq!(y, x.exp_u64(ALPHA_INV));
+    }
+
+    apply_sbox(&mut state);
+    assert_eq!(state, original);
+}
+
+#[test]
+fn test_apply_sbox() {
+    let mut state = [0, 1, 2, 3, PRIME - 1].map(FieldElement::new);
+    apply_sbox(&mut state);
+    assert_eq!(state, [0, 1, 128, 2187, PRIME - 1].map(FieldElement::new));
+
+    let mut state = test_array::<32>(4);
+    let original = state;
+    apply_sbox(&mut state);
+    for (&x, &y) in original.iter().zip(state.iter()) {
+        assert_eq!(y, x.exp_u64(ALPHA));
+    }
+
+    apply_inv_sbox(&mut state);
+    assert_eq!(state, original);
+}
+
+#[test]
+fn test_rescue_xlix() {
+    let (round_constants, mds) = test_params();
+
+    let mut state = [0u64, 1, 2, 3].map(FieldElement::new);
+    rescue_xlix(&mut state, &round_constants, &mds);
+
+    let expected = [
+        16586160440193410798u64,
+        13808084424872795445,
+        9350914659041658546,
+        9336274911743689314,
+    ]
+    .map(FieldElement::new);
+    assert_eq!(state, expected);
+}
+
+fn circulant<const N: usize>(column: &[FieldElement; N]) -> [[FieldElement; N]; N] {
+    let mut matrix = [[FieldElement::ZERO; N]; N];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = column[(N + i - j) % N];
+        }
+    }
+    matrix
+}
+
+fn check_mds_ntt<const N: usize>() {
+    let mds = circulant(&test_array::<N>(1));
+    let mds_ntt = circulant_mds_ntt(&mds).expect("the matrix is circulant");
+
+    for seed in 0..8 {
+        let mut expected = test_array::<N>(100 * seed);
+        let mut state = expected;
+        apply_mds(&mut expected, &mds);
+        apply_mds_ntt(&mut state, &mds_ntt);
+        assert_eq!(state, expected);
+    }
+}
+
+#[test]
+fn test_apply_mds_ntt() {
+    check_mds_ntt::<1>();
+    check_mds_ntt::<2>();
+    check_mds_ntt::<4>();
+    check_mds_ntt::<8>();
+    check_mds_ntt::<16>();
+}
+
+#[test]
+fn test_circulant_mds_ntt_unsupported() {
+    // the width is not a power of two
+    let mds = circulant(&test_array::<12>(1));
+    assert!(circulant_mds_ntt(&mds).is_none());
+
+    // the matrix is not circulant
+    let mut mds = circulant(&test_array::<8>(1));
+    mds[3][5] += FieldElement::ONE;
+    assert!(circulant_mds_ntt(&mds).is_none());
+    assert!(circulant_mds_ntt(&crate::generate_params::generate_mds::<8>().unwrap()).is_none());
+}
+
+#[test]
+fn test_rescue_xlix_inverse() {
+    let (round_constants, mds) = test_params();
+    let mds_inv = invert_matrix(&mds).expect("the matrix must be invertible");
+
+    for seed in 0..4 {
+        let original = test_array::<WIDTH>(10 * seed);
+
+        let mut state = original;
+        rescue_xlix(&mut state, &round_constants, &mds);
+        assert_ne!(state, original);
+        rescue_xlix_inverse(&mut state, &round_constants, &mds_inv);
+        assert_eq!(state, original);
+
+        rescue_xlix_inverse(&mut state, &round_constants, &mds_inv);
+        rescue_xlix(&mut state, &round_constants, &mds);
+        assert_eq!(state, original);
+    }
+}
+
+#[test]
+fn test_rescue_xlix_inverse_standard() {
+    use crate::{
+        generate_params::{compute_round_constants, generate_mds},
+        hash::{NUM_ROUNDS, RATE, SECURITY_LEVEL, WIDTH},
+    };
+
+    let round_constants =
+        compute_round_constants::<WIDTH, RATE, NUM_ROUNDS>(SECURITY_LEVEL).unwrap();
+    let mds = generate_mds::<WIDTH>().unwrap();
+    let mds_inv = invert_matrix(&mds).expect("the MDS matrix must be invertible");
+
+    let original = test_array::<WIDTH>(7);
+    let mut state = original;
+    rescue_xlix(&mut state, &round_constants, &mds);
+    rescue_xlix_inverse(&mut state, &round_constants, &mds_inv);
+    assert_eq!(state, original);
+}
+
+#[test]
+fn test_is_mds() {
+    use crate::generate_params::generate_mds;
+
+    assert!(is_mds(&generate_mds::<2>().unwrap()));
+    assert!(is_mds(&generate_mds::<4>().unwrap()));
+    assert!(is_mds(&generate_mds::<8>().unwrap()));
+
+    // the identity has zero entries, i.e. singular 1 x 1 submatrices
+    let mut identity = [[FieldElement::ZERO; 4]; 4];
+    for (i, row) in identity.iter_mut().enumerate() {
+        row[i] = FieldElement::ONE;
+    }
+    assert!(!is_mds(&identity));
+
+    // no zero entry, but the top-left 2 x 2 minor is 1 * 4 - 2 * 2 = 0
+    let m = [[1u64, 2, 3], [2, 4, 5], [7, 11, 13]].map(|row| row.map(FieldElement::new));
+    assert!(!is_mds(&m));
+
+    // the same with the singular minor away from the leading rows and columns
+    let m = [[1u64, 3, 5], [7, 1, 2], [11, 2, 4]].map(|row| row.map(FieldElement::new));
+    assert!(!is_mds(&m));
+}
+
+fn identity<const N: usize>() -> [[FieldElement; N]; N] {
+    let mut matrix = [[FieldElement::ZERO; N]; N];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[i] = FieldElement::ONE;
+    }
+    matrix
+}
+
+fn mat_mul<const N: usize>(
+    a: &[[FieldElement; N]; N],
+    b: &[[FieldElement; N]; N],
+) -> [[FieldElement; N]; N] {
+    let mut result = [[FieldElement::ZERO; N]; N];
+    for (result_row, a_row) in result.iter_mut().zip(a.iter()) {
+        for (j, entry) in result_row.iter_mut().enumerate() {
+            *entry = a_row.iter().zip(b.iter()).map(|(&x, b_row)| x * b_row[j]).sum();
+        }
+    }
+    result
+}
+
+#[test]
+fn test_determinant() {
+    assert_eq!(determinant(&identity::<1>()), FieldElement::ONE);
+    assert_eq!(determinant(&identity::<5>()), FieldElement::ONE);
+
+    let m = [[2u64, 3], [5, 7]].map(|row| row.map(FieldElement::new));
+    assert_eq!(determinant(&m), FieldElement::from(-1i64));
+
+    // the first column is zero, so a row swap is needed and flips the sign
+    let m = [[0u64, 1, 0], [1, 0, 0], [0, 0, 3]].map(|row| row.map(FieldElement::new));
+    assert_eq!(determinant(&m), FieldElement::from(-3i64));
+
+    let m = [[1u64, 2, 3], [2, 4, 6], [7, 11, 13]].map(|row| row.map(FieldElement::new));
+    assert_eq!(determinant(&m), FieldElement::ZERO);
+
+    // det(AB) = det(A) det(B)
+    let a = circulant(&test_array::<6>(1));
+    let b = circulant(&test_array::<6>(2));
+    assert_eq!(determinant(&mat_mul(&a, &b)), determinant(&a) * determinant(&b));
+}
+
+#[test]
+fn test_invert_matrix() {
+    let m: [[FieldElement; 6]; 6] = core::array::from_fn(|i| test_array(i as u64));
+    assert_ne!(determinant(&m), FieldElement::ZERO);
+
+    let m_inv = invert_matrix(&m).expect("the matrix must be invertible");
+    assert_eq!(mat_mul(&m, &m_inv), identity());
+    assert_eq!(mat_mul(&m_inv, &m), identity());
+    assert_eq!(determinant(&m_inv), determinant(&m).inv());
+
+    assert_eq!(invert_matrix(&identity::<4>()), Some(identity()));
+
+    // a repeated row makes the matrix singular
+    let mut singular = m;
+    singular[3] = singular[1];
+    assert_eq!(determinant(&singular), FieldElement::ZERO);
+    assert_eq!(invert_matrix(&singular), None);
+    assert_eq!(invert_matrix(&[[FieldElement::ZERO; 3]; 3]), None);
+}
+
+#[test]
+fn test_rescue_xlix_generic() {
+    use crate::field::Field;
+
+    // round trip through the permutation with only the trait bound available
+    fn round_trip<F: Field, const W: usize, const N: usize>(
+        state: &[F; W],
+        round_constants: &[[[F; W]; N]; 2],
+        mds: &[[F; W]; W],
+        mds_inv: &[[F; W]; W],
+    ) -> ([F; W], [F; W]) {
+        let mut permuted = *state;
+        rescue_xlix(&mut permuted, round_constants, mds);
+        let mut restored = permuted;
+        rescue_xlix_inverse(&mut restored, round_constants, mds_inv);
+        (permuted, restored)
+    }
+
+    let (round_constants, mds) = test_params();
+    let mds_inv = invert_matrix(&mds).expect("the matrix must be invertible");
+    let original = test_array::<WIDTH>(3);
+
+    let mut expected = original;
+    rescue_xlix(&mut expected, &round_constants, &mds);
+    let (permuted, restored) = round_trip(&original, &round_constants, &mds, &mds_inv);
+    assert_eq!(permuted, expected);
+    assert_eq!(restored, original);
+
+    // the default methods of the trait agree with the ones of FieldElement
+    let mut state = original;
+    for element in state.iter_mut() {
+        *element = element.exp_u64(<FieldElement as Field>::ALPHA_INV);
+    }
+    let mut expected = original;
+    <FieldElement as Field>::inv_sbox(&mut expected);
+    assert_eq!(state, expected);
+
+    let mut state = original;
+    for element in state.iter_mut() {
+        *element = element.exp_u64(<FieldElement as Field>::ALPHA);
+    }
+    let mut expected = original;
+    <FieldElement as Field>::sbox(&mut expected);
+    assert_eq!(state, expected);
+
+    let (a, b) = (test_array::<WIDTH>(5), test_array::<WIDTH>(6));
+    let expected = a.iter().zip(b.iter()).fold(FieldElement::ZERO, |acc, (&x, &y)| acc + x * y);
+    assert_eq!(<FieldElement as Field>::inner_product(&a, &b), expected);
+}
+
+#[test]
+fn test_ntt() {
+    for n in [1, 2, 8, 1024] {
+        let original = test_elements(n, n as u64);
+        let mut values = original.clone();
+        ntt(&mut values);
+        if n > 1 {
+            assert_ne!(values, original);
+        }
+        intt(&mut values);
+        assert_eq!(values, original);
+    }
+
+    // the transform evaluates the polynomial at the powers of the root of unity
+    let coefficients = test_elements(8, 3);
+    let mut values = coefficients.clone();
+    ntt(&mut values);
+    let root = FieldElement::get_root_of_unity(3);
+    for (i, &value) in values.iter().enumerate() {
+        let x = root.exp_u64(i as u64);
+        let expected = coefficients.iter().rev().fold(FieldElement::ZERO, |acc, &c| acc * x + c);
+        assert_eq!(value, expected);
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_ntt_parallel() {
+    use super::ntt::{ntt_in_place_par, ntt_in_place_seq};
+
+    for log_n in [1, 4, 12, 16] {
+        let root = FieldElement::get_root_of_unity(log_n);
+        let mut sequential = test_elements(1 << log_n, 7);
+        let mut parallel = sequential.clone();
+        ntt_in_place_seq(&mut sequential, root);
+        ntt_in_place_par(&mut parallel, root);
+        assert_eq!(parallel, sequential);
+    }
+}
+
+#[test]
+fn test_ntt_with_twiddles() {
+    use super::ntt::{ntt_with_twiddles, stage_twiddles};
+
+    for log_n in 0..6 {
+        let n = 1 << log_n;
+        let root = FieldElement::get_root_of_unity(log_n);
+        let mut twiddles = vec![FieldElement::ZERO; n - 1];
+        stage_twiddles(&mut twiddles, root);
+
+        let mut expected = test_elements(n, 3);
+        let mut actual = expected.clone();
+        ntt(&mut expected);
+        ntt_with_twiddles(&mut actual, &twiddles);
+        assert_eq!(actual, expected, "{n} values");
+    }
+}
+
+#[test]
+fn test_ntt_convolution() {
+    let n = 16;
+    let (a, b) = (test_elements(n, 1), test_elements(n, 100));
+
+    let mut expected = vec![FieldElement::ZERO; n];
+    for i in 0..n {
+        for j in 0..n {
+            expected[(i + j) % n] += a[i] * b[j];
+        }
+    }
+
+    let (mut a_ntt, mut b_ntt) = (a.clone(), b.clone());
+    ntt(&mut a_ntt);
+    ntt(&mut b_ntt);
+    let mut product: Vec<_> = a_ntt.iter().zip(b_ntt.iter()).map(|(&x, &y)| x * y).collect();
+    intt(&mut product);
+    assert_eq!(product, expected);
+}
+
+#[test]
+fn test_bit_reverse() {
+    let mut values: Vec<usize> = (0..8).collect();
+    bit_reverse(&mut values);
+    assert_eq!(values, [0, 4, 2, 6, 1, 5, 3, 7]);
+    bit_reverse(&mut values);
+    assert_eq!(values, (0..8).collect::<Vec<_>>());
+
+    let mut values = [7];
+    bit_reverse(&mut values);
+    assert_eq!(values, [7]);
+}
+
+#[test]
+#[should_panic]
+fn test_ntt_not_power_of_two() {
+    ntt(&mut test_elements(12, 0));
+}
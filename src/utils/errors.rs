@@ -1,14 +1,49 @@
-use std::fmt::{Display, Formatter, Result};
+use core::fmt::{Display, Formatter, Result};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FieldError {
-    DeserializationError,
+    NonCanonical,
+    ParseError,
+    InvalidLength,
+    DivisionByZero,
 }
 
 impl Display for FieldError {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
-            Self::DeserializationError => write!(f, "Deserialization error due to invalid value"),
+            Self::NonCanonical => write!(f, "Value not less than the field modulus"),
+            Self::ParseError => write!(f, "Parse error due to an invalid decimal string"),
+            Self::InvalidLength => write!(f, "Invalid number of bytes for a field element"),
+            Self::DivisionByZero => write!(f, "Division by zero"),
         }
     }
 }
+
+impl core::error::Error for FieldError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamError {
+    InvalidRoundConstant,
+    ZeroMdsEntry,
+    RateExceedsWidth,
+    UnsupportedWidth,
+    InvalidSecurityLevel,
+    DigestExceedsRate,
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Self::InvalidRoundConstant => {
+                write!(f, "Round constant derived from an invalid chunk of bytes")
+            }
+            Self::ZeroMdsEntry => write!(f, "MDS matrix with a zero entry"),
+            Self::RateExceedsWidth => write!(f, "Rate greater than the state width"),
+            Self::UnsupportedWidth => write!(f, "Unsupported state width"),
+            Self::InvalidSecurityLevel => write!(f, "Invalid security level"),
+            Self::DigestExceedsRate => write!(f, "Two digests greater than the rate"),
+        }
+    }
+}
+
+impl core::error::Error for ParamError {}
@@ -1 +1,4 @@
 pub mod errors;
+pub(crate) mod once;
+#[cfg(test)]
+pub(crate) mod test_utils;
@@ -0,0 +1,58 @@
+use alloc::boxed::Box;
+use core::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+// STRUCTS
+// =============================================================================
+
+/// A cell that is initialized at most once and then lives for the rest of the
+/// program, like `std::sync::OnceLock` but available without `std`.
+///
+/// The value is allocated on the heap during initialization. If several threads
+/// initialize the cell concurrently, each of them computes a value, but only the
+/// first one to finish is stored and the others are dropped.
+pub(crate) struct OnceBox<T> {
+    ptr: AtomicPtr<T>,
+}
+
+// SAFETY: the value is only ever shared through `&T`, after being fully written
+// and published with release/acquire ordering.
+unsafe impl<T: Send + Sync> Sync for OnceBox<T> {}
+
+// IMPLEMENTATIONS
+// =============================================================================
+
+impl<T> OnceBox<T> {
+    /// Create a new, uninitialized cell.
+    pub(crate) const fn new() -> Self {
+        Self {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Return the value of the cell, initializing it with f if it is empty.
+    pub(crate) fn get_or_init(&'static self, f: impl FnOnce() -> T) -> &'static T {
+        let mut current = self.ptr.load(Ordering::Acquire);
+        if current.is_null() {
+            let new = Box::into_raw(Box::new(f()));
+            match self.ptr.compare_exchange(
+                ptr::null_mut(),
+                new,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => current = new,
+                Err(winner) => {
+                    // SAFETY: `new` was never published, so this is its only owner.
+                    drop(unsafe { Box::from_raw(new) });
+                    current = winner;
+                }
+            }
+        }
+        // SAFETY: a non-null pointer was published by a successful exchange and
+        // is never freed, since the cell is 'static.
+        unsafe { &*current }
+    }
+}
@@ -0,0 +1,27 @@
+use alloc::vec::Vec;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::field_element::FieldElement;
+
+// TEST HELPERS
+// =============================================================================
+
+/// Return a random number generator seeded with the given seed, so that the
+/// tests are reproducible and different seeds give independent values.
+pub(crate) fn test_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Return n pseudo-random field elements, drawn from `test_rng(seed)`.
+pub(crate) fn test_elements(n: usize, seed: u64) -> Vec<FieldElement> {
+    let mut rng = test_rng(seed);
+    (0..n).map(|_| FieldElement::new(rng.gen())).collect()
+}
+
+/// Return N pseudo-random field elements as an array, drawn from
+/// `test_rng(seed)`.
+pub(crate) fn test_array<const N: usize>(seed: u64) -> [FieldElement; N] {
+    let mut rng = test_rng(seed);
+    core::array::from_fn(|_| FieldElement::new(rng.gen()))
+}